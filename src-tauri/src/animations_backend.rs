@@ -0,0 +1,532 @@
+use hyprlang::Hyprland;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::State;
+
+use crate::backend_error::BackendError;
+use crate::config_session::{ConfigSession, MutationResult};
+use crate::handler_ids::{HandlerEntry, handler_entries};
+use crate::handlers_backend::{
+    format_args, insert_handler_call, remove_handler_call, replace_handler_call,
+};
+use crate::journal_backend::Journal;
+
+/// Hyprland's animation tree as (name, parent). A node without an `animation =`
+/// line of its own uses the config of its closest configured ancestor.
+const ANIMATION_TREE: &[(&str, Option<&str>)] = &[
+    ("global", None),
+    ("windows", Some("global")),
+    ("windowsIn", Some("windows")),
+    ("windowsOut", Some("windows")),
+    ("windowsMove", Some("windows")),
+    ("layers", Some("global")),
+    ("layersIn", Some("layers")),
+    ("layersOut", Some("layers")),
+    ("fade", Some("global")),
+    ("fadeIn", Some("fade")),
+    ("fadeOut", Some("fade")),
+    ("fadeSwitch", Some("fade")),
+    ("fadeShadow", Some("fade")),
+    ("fadeDim", Some("fade")),
+    ("fadeLayers", Some("fade")),
+    ("fadeLayersIn", Some("fadeLayers")),
+    ("fadeLayersOut", Some("fadeLayers")),
+    ("fadePopups", Some("fade")),
+    ("fadePopupsIn", Some("fadePopups")),
+    ("fadePopupsOut", Some("fadePopups")),
+    ("fadeDpms", Some("fade")),
+    ("border", Some("global")),
+    ("borderangle", Some("global")),
+    ("workspaces", Some("global")),
+    ("workspacesIn", Some("workspaces")),
+    ("workspacesOut", Some("workspaces")),
+    ("specialWorkspace", Some("workspaces")),
+    ("specialWorkspaceIn", Some("specialWorkspace")),
+    ("specialWorkspaceOut", Some("specialWorkspace")),
+    ("zoomFactor", Some("global")),
+    ("monitorAdded", Some("global")),
+];
+
+/// Curves Hyprland defines itself
+const BUILTIN_BEZIERS: &[(&str, [f64; 4])] = &[
+    ("default", [0.0, 0.75, 0.15, 1.0]),
+    ("linear", [0.0, 0.0, 1.0, 1.0]),
+];
+
+/// Names of every node in the animation tree
+pub fn animation_names() -> Vec<&'static str> {
+    ANIMATION_TREE.iter().map(|(name, _)| *name).collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Bezier {
+    /// Stable id of the `bezier =` line, `None` for built-in curves
+    pub id: Option<String>,
+    pub name: String,
+    pub x0: f64,
+    pub y0: f64,
+    pub x1: f64,
+    pub y1: f64,
+    pub source_file: Option<String>,
+    pub line: Option<usize>,
+    /// Animations whose effective config uses this curve
+    pub used_by: Vec<String>,
+}
+
+/// An `animation =` line
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnimationEntry {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub speed: Option<f64>,
+    pub curve: Option<String>,
+    pub style: Option<String>,
+    pub source_file: Option<String>,
+    pub line: Option<usize>,
+    /// Why Hyprland rejects the line, e.g. an undefined curve
+    pub error: Option<String>,
+}
+
+/// A node of the animation tree with the config in effect for it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnimationNode {
+    pub name: String,
+    pub parent: Option<String>,
+    /// The line configuring this node itself, the last one when there are several
+    pub entry: Option<AnimationEntry>,
+    /// Node whose config applies, the node itself when `entry` is set
+    pub inherited_from: String,
+    pub enabled: bool,
+    pub speed: f64,
+    pub curve: String,
+    pub style: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnimationsConfig {
+    pub animations: Vec<AnimationNode>,
+    pub beziers: Vec<Bezier>,
+    /// Lines that don't belong to a known animation or are invalid
+    pub invalid: Vec<AnimationEntry>,
+}
+
+fn relative_file(entry: &HandlerEntry, hypr_dir: &Path) -> Option<String> {
+    entry
+        .file
+        .as_ref()
+        .map(|p| p.strip_prefix(hypr_dir).unwrap_or(p).display().to_string())
+}
+
+fn parse_bezier(entry: &HandlerEntry, hypr_dir: &Path) -> Option<Bezier> {
+    let parts: Vec<&str> = entry.value.split(',').map(str::trim).collect();
+    let [name, x0, y0, x1, y1] = parts.as_slice() else {
+        return None;
+    };
+
+    Some(Bezier {
+        id: Some(entry.id.clone()),
+        name: name.to_string(),
+        x0: x0.parse().ok()?,
+        y0: y0.parse().ok()?,
+        x1: x1.parse().ok()?,
+        y1: y1.parse().ok()?,
+        source_file: relative_file(entry, hypr_dir),
+        line: entry.line,
+        used_by: Vec::new(),
+    })
+}
+
+/// Parse an `animation =` line, checking it the way Hyprland does
+fn parse_animation(entry: &HandlerEntry, hypr_dir: &Path, curves: &[String]) -> AnimationEntry {
+    let parts: Vec<&str> = entry.value.split(',').map(str::trim).collect();
+    let part = |i: usize| {
+        parts
+            .get(i)
+            .filter(|p| !p.is_empty())
+            .map(|p| p.to_string())
+    };
+
+    let name = part(0).unwrap_or_default();
+    let enabled = part(1);
+    let speed = part(2).and_then(|s| s.parse::<f64>().ok());
+    let curve = part(3);
+
+    let error = if !animation_names().contains(&name.as_str()) {
+        Some(format!("Unknown animation '{}'", name))
+    } else if !matches!(enabled.as_deref(), Some("0" | "1")) {
+        Some("The second argument must be 0 or 1".to_string())
+    } else if enabled.as_deref() == Some("0") {
+        // Disabled animations ignore the rest of the line
+        None
+    } else if !speed.is_some_and(|s| s > 0.0) {
+        Some("Speed must be a number above 0".to_string())
+    } else {
+        curve
+            .as_ref()
+            .filter(|c| !curves.contains(c))
+            .map(|c| format!("Curve '{}' is not defined", c))
+    };
+
+    AnimationEntry {
+        id: entry.id.clone(),
+        name,
+        enabled: enabled.as_deref() == Some("1"),
+        speed,
+        curve,
+        style: part(4),
+        source_file: relative_file(entry, hypr_dir),
+        line: entry.line,
+        error,
+    }
+}
+
+/// Config of a node from its own line: disabled nodes get speed 1 and the
+/// default curve, like in Hyprland
+fn node_config(entry: &AnimationEntry) -> (bool, f64, String, Option<String>) {
+    if entry.enabled {
+        (
+            true,
+            entry.speed.unwrap_or(1.0),
+            entry.curve.clone().unwrap_or_else(|| "default".to_string()),
+            entry.style.clone(),
+        )
+    } else {
+        (false, 1.0, "default".to_string(), None)
+    }
+}
+
+fn read_animations(hypr: &Hyprland, config_path: &Path) -> AnimationsConfig {
+    let hypr_dir = config_path.parent().unwrap_or(Path::new("/"));
+
+    let mut beziers: Vec<Bezier> = BUILTIN_BEZIERS
+        .iter()
+        .map(|(name, [x0, y0, x1, y1])| Bezier {
+            id: None,
+            name: name.to_string(),
+            x0: *x0,
+            y0: *y0,
+            x1: *x1,
+            y1: *y1,
+            source_file: None,
+            line: None,
+            used_by: Vec::new(),
+        })
+        .collect();
+    for bezier in handler_entries(hypr, "bezier")
+        .iter()
+        .filter_map(|entry| parse_bezier(entry, hypr_dir))
+    {
+        // A later definition replaces an earlier one of the same name
+        beziers.retain(|b| b.name != bezier.name);
+        beziers.push(bezier);
+    }
+    let curves: Vec<String> = beziers.iter().map(|b| b.name.clone()).collect();
+
+    let mut explicit: HashMap<String, AnimationEntry> = HashMap::new();
+    let mut invalid = Vec::new();
+    for entry in handler_entries(hypr, "animation") {
+        let animation = parse_animation(&entry, hypr_dir, &curves);
+        if animation.error.is_some() {
+            invalid.push(animation);
+        } else {
+            explicit.insert(animation.name.clone(), animation);
+        }
+    }
+
+    // The tree lists parents before their children, so resolved parents are at hand
+    let mut animations: Vec<AnimationNode> = Vec::new();
+    for (name, parent) in ANIMATION_TREE {
+        let entry = explicit.remove(*name);
+        let resolved_parent =
+            parent.and_then(|parent| animations.iter().find(|n| n.name == parent));
+
+        let node = match (&entry, resolved_parent) {
+            (Some(entry), _) => {
+                let (enabled, speed, curve, style) = node_config(entry);
+                AnimationNode {
+                    name: name.to_string(),
+                    parent: parent.map(str::to_string),
+                    entry: Some(entry.clone()),
+                    inherited_from: name.to_string(),
+                    enabled,
+                    speed,
+                    curve,
+                    style,
+                }
+            }
+            // Hyprland configures borderangle as disabled, so it doesn't inherit
+            (None, Some(_)) if *name == "borderangle" => AnimationNode {
+                name: name.to_string(),
+                parent: parent.map(str::to_string),
+                entry: None,
+                inherited_from: name.to_string(),
+                enabled: false,
+                speed: 1.0,
+                curve: "default".to_string(),
+                style: None,
+            },
+            (None, Some(parent)) => AnimationNode {
+                name: name.to_string(),
+                entry: None,
+                parent: Some(parent.name.clone()),
+                ..parent.clone()
+            },
+            // Hyprland's defaults, also used should the tree ever list a child before
+            // its parent
+            (None, None) => AnimationNode {
+                name: name.to_string(),
+                parent: parent.map(str::to_string),
+                entry: None,
+                inherited_from: name.to_string(),
+                enabled: true,
+                speed: 8.0,
+                curve: "default".to_string(),
+                style: None,
+            },
+        };
+        animations.push(node);
+    }
+
+    for bezier in &mut beziers {
+        bezier.used_by = animations
+            .iter()
+            .filter(|n| n.enabled && n.curve == bezier.name)
+            .map(|n| n.name.clone())
+            .collect();
+    }
+
+    AnimationsConfig {
+        animations,
+        beziers,
+        invalid,
+    }
+}
+
+/// Tauri command returning the animation tree with inherited values, the curves and
+/// any invalid `animation =` lines
+#[tauri::command]
+pub fn get_animations(session: State<'_, ConfigSession>) -> Result<AnimationsConfig, BackendError> {
+    session.read(|hypr, config_path| Ok(read_animations(hypr, config_path)))
+}
+
+fn format_number(value: f64) -> String {
+    // Drop float noise such as 0.30000000000000004
+    let rounded = (value * 1e6).round() / 1e6;
+    rounded.to_string()
+}
+
+/// Tauri command configuring animation `name`, replacing the last line that sets it
+/// or adding one. Curves must be defined.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn set_animation(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    name: String,
+    enabled: bool,
+    speed: Option<f64>,
+    curve: Option<String>,
+    style: Option<String>,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let config = session.read(|hypr, config_path| Ok(read_animations(hypr, config_path)))?;
+
+    let mut args = vec![name.clone(), if enabled { "1" } else { "0" }.to_string()];
+    if enabled {
+        let speed = speed
+            .filter(|s| *s > 0.0)
+            .ok_or_else(|| BackendError::invalid_input("Speed must be a number above 0"))?;
+        let curve = curve.unwrap_or_else(|| "default".to_string());
+
+        if !config.beziers.iter().any(|b| b.name == curve) {
+            return Err(
+                BackendError::invalid_input(format!("Curve '{}' is not defined", curve))
+                    .with_hint("Add it with add_bezier first"),
+            );
+        }
+
+        args.push(format_number(speed));
+        args.push(curve);
+        args.extend(style.filter(|s| !s.trim().is_empty()));
+    }
+    let value = format_args("animation", &args)?;
+
+    let existing = config
+        .animations
+        .into_iter()
+        .find(|n| n.name == name)
+        .and_then(|n| n.entry);
+
+    match existing {
+        Some(entry) => replace_handler_call(
+            &session,
+            &journal,
+            "animation".to_string(),
+            entry.id,
+            value,
+            dry_run,
+        ),
+        None => insert_handler_call(&session, &journal, "animation", value, target, dry_run),
+    }
+}
+
+/// Tauri command removing an `animation =` line, so the node inherits again
+#[tauri::command]
+pub fn delete_animation(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    remove_handler_call(&session, &journal, "animation", &id, dry_run)
+}
+
+/// Validate the control points; x must stay within 0..1 for the curve to be a
+/// function of time
+fn bezier_args(name: &str, points: [f64; 4]) -> Result<Vec<String>, BackendError> {
+    let [x0, _, x1, _] = points;
+    if !(0.0..=1.0).contains(&x0) || !(0.0..=1.0).contains(&x1) {
+        return Err(BackendError::invalid_input(
+            "x0 and x1 must be between 0 and 1",
+        ));
+    }
+    if points.iter().any(|p| !p.is_finite()) {
+        return Err(BackendError::invalid_input(
+            "Control points must be numbers",
+        ));
+    }
+
+    let mut args = vec![name.trim().to_string()];
+    args.extend(points.iter().map(|p| format_number(*p)));
+    Ok(args)
+}
+
+fn find_bezier(config: &AnimationsConfig, id: &str) -> Result<Bezier, BackendError> {
+    config
+        .beziers
+        .iter()
+        .find(|b| b.id.as_deref() == Some(id))
+        .cloned()
+        .ok_or_else(|| {
+            BackendError::not_found("The curve no longer exists").with_hint("Reload and try again")
+        })
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn add_bezier(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    name: String,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let config = session.read(|hypr, config_path| Ok(read_animations(hypr, config_path)))?;
+    if config.beziers.iter().any(|b| b.name == name.trim()) {
+        return Err(BackendError::invalid_input(format!(
+            "Curve '{}' already exists",
+            name.trim()
+        )));
+    }
+
+    let value = format_args("bezier", &bezier_args(&name, [x0, y0, x1, y1])?)?;
+    insert_handler_call(&session, &journal, "bezier", value, target, dry_run)
+}
+
+/// Tauri command changing a curve. Renaming fails while animations use the old name.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn edit_bezier(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    name: String,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let config = session.read(|hypr, config_path| Ok(read_animations(hypr, config_path)))?;
+    let bezier = find_bezier(&config, &id)?;
+
+    if bezier.name != name.trim() {
+        if !bezier.used_by.is_empty() {
+            return Err(BackendError::invalid_input(format!(
+                "Curve '{}' is used by {}",
+                bezier.name,
+                bezier.used_by.join(", ")
+            )));
+        }
+        if config.beziers.iter().any(|b| b.name == name.trim()) {
+            return Err(BackendError::invalid_input(format!(
+                "Curve '{}' already exists",
+                name.trim()
+            )));
+        }
+    }
+
+    let value = format_args("bezier", &bezier_args(&name, [x0, y0, x1, y1])?)?;
+    replace_handler_call(&session, &journal, "bezier".to_string(), id, value, dry_run)
+}
+
+/// Tauri command removing a curve that no animation uses
+#[tauri::command]
+pub fn delete_bezier(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let config = session.read(|hypr, config_path| Ok(read_animations(hypr, config_path)))?;
+    let bezier = find_bezier(&config, &id)?;
+
+    let referenced: Vec<&str> = config
+        .animations
+        .iter()
+        .filter_map(|n| n.entry.as_ref())
+        .filter(|e| e.curve.as_deref() == Some(bezier.name.as_str()))
+        .map(|e| e.name.as_str())
+        .collect();
+    if !referenced.is_empty() {
+        return Err(BackendError::invalid_input(format!(
+            "Curve '{}' is used by {}",
+            bezier.name,
+            referenced.join(", ")
+        )));
+    }
+
+    remove_handler_call(&session, &journal, "bezier", &id, dry_run)
+}
+
+/// Tauri command sampling the curve through (0,0), (x0,y0), (x1,y1), (1,1) at
+/// `count` evenly spaced parameters, as (x, y) points for plotting
+#[tauri::command]
+pub fn sample_bezier(
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    count: Option<usize>,
+) -> Result<Vec<[f64; 2]>, BackendError> {
+    let count = count.unwrap_or(64).clamp(2, 1024);
+
+    let coordinate = |t: f64, p1: f64, p2: f64| {
+        let u = 1.0 - t;
+        3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+    };
+
+    Ok((0..count)
+        .map(|i| {
+            let t = i as f64 / (count - 1) as f64;
+            [coordinate(t, x0, x1), coordinate(t, y0, y1)]
+        })
+        .collect())
+}
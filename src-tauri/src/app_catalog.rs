@@ -0,0 +1,422 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backend_error::BackendError;
+use crate::config_paths::{xdg_data_dirs, xdg_data_home};
+use crate::icon_theme::{DEFAULT_ICON_SIZE, IconResolver};
+
+/// An application from a `.desktop` file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DesktopApp {
+    /// Desktop file id, e.g. `org.gnome.Nautilus`
+    pub id: String,
+    pub name: String,
+    pub generic_name: Option<String>,
+    pub comment: Option<String>,
+    /// Command line with the `%f`/`%U`/... field codes removed
+    pub exec: String,
+    pub icon: Option<String>,
+    /// `icon` resolved to a file in the current icon theme
+    pub icon_path: Option<String>,
+    pub categories: Vec<String>,
+    pub keywords: Vec<String>,
+    /// Window class the app sets, usable in window rules
+    pub startup_wm_class: Option<String>,
+    pub terminal: bool,
+    /// Hidden from menus, but still launchable
+    pub no_display: bool,
+    pub path: String,
+}
+
+/// `applications` directories in lookup order; the first file with a given id wins
+fn application_dirs() -> Vec<PathBuf> {
+    xdg_data_home()
+        .into_iter()
+        .chain(xdg_data_dirs())
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+/// Every `.desktop` file below `dir`, with its desktop file id
+fn desktop_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut entries: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    entries.sort();
+
+    for path in entries {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        // Files in subdirectories get the directory in their id, `kde4/foo` -> `kde4-foo`
+        if path.is_dir() {
+            desktop_files(&path, &format!("{}{}-", prefix, name), files);
+        } else if let Some(stem) = name.strip_suffix(".desktop") {
+            files.push((format!("{}{}", prefix, stem), path));
+        }
+    }
+}
+
+/// Undo the escapes of string values: `\s`, `\n`, `\t`, `\r` and `\\`
+fn unescape(value: &str) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Split a `;` separated list
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Split an `Exec` value into arguments. Arguments may be quoted with `"`, inside
+/// which `\"`, `` \` ``, `\$` and `\\` are escapes.
+fn split_exec(exec: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            '\\' if quoted => match chars.next() {
+                Some(escaped @ ('"' | '`' | '$' | '\\')) => arg.push(escaped),
+                Some(other) => {
+                    arg.push('\\');
+                    arg.push(other);
+                }
+                None => arg.push('\\'),
+            },
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut arg));
+                    in_arg = false;
+                }
+            }
+            c => {
+                arg.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(arg);
+    }
+    args
+}
+
+/// Quote an argument for a shell command line when it needs it
+fn quote_arg(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_./:=@%+,".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Drop the field codes a launcher would expand (`%f`, `%U`, `%i`, ...) and the
+/// `@@` markers flatpak adds around them. Field codes inside an argument, as in
+/// `sh -c "foo %f"`, are removed from it; `%%` becomes `%`.
+fn strip_field_codes(exec: &str) -> String {
+    split_exec(exec)
+        .into_iter()
+        .filter(|arg| !arg.starts_with("@@"))
+        .filter_map(|arg| {
+            let mut stripped = String::new();
+            let mut chars = arg.chars();
+            while let Some(c) = chars.next() {
+                if c != '%' {
+                    stripped.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('%') => stripped.push('%'),
+                    Some(code) if "fFuUdDnNickvm".contains(code) => {}
+                    Some(other) => {
+                        stripped.push('%');
+                        stripped.push(other);
+                    }
+                    None => stripped.push('%'),
+                }
+            }
+            // An argument that was only a field code goes away entirely
+            (!stripped.is_empty() || arg.is_empty()).then_some(stripped)
+        })
+        .map(|arg| quote_arg(&arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse the `[Desktop Entry]` group. Entries that aren't applications, are hidden or
+/// can't be launched are skipped.
+fn parse_desktop_file(id: String, path: &Path, contents: &str) -> Option<DesktopApp> {
+    let mut in_entry = false;
+    let mut app = DesktopApp {
+        id,
+        name: String::new(),
+        generic_name: None,
+        comment: None,
+        exec: String::new(),
+        icon: None,
+        icon_path: None,
+        categories: Vec::new(),
+        keywords: Vec::new(),
+        startup_wm_class: None,
+        terminal: false,
+        no_display: false,
+        path: path.display().to_string(),
+    };
+    let mut kind = None;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry || line.starts_with('#') {
+            continue;
+        }
+
+        // Localized keys such as `Name[de]` are ignored
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = unescape(value.trim());
+
+        match key.trim() {
+            "Type" => kind = Some(value),
+            "Name" => app.name = value,
+            "GenericName" => app.generic_name = Some(value),
+            "Comment" => app.comment = Some(value),
+            "Exec" => app.exec = strip_field_codes(&value),
+            "Icon" => app.icon = Some(value),
+            "Categories" => app.categories = split_list(&value),
+            "Keywords" => app.keywords = split_list(&value),
+            "StartupWMClass" => app.startup_wm_class = Some(value),
+            "Terminal" => app.terminal = value == "true",
+            "NoDisplay" => app.no_display = value == "true",
+            "Hidden" if value == "true" => return None,
+            _ => {}
+        }
+    }
+
+    (kind.as_deref() == Some("Application") && !app.name.is_empty() && !app.exec.is_empty())
+        .then_some(app)
+}
+
+/// Every installed application, sorted by name
+pub fn load_applications() -> Vec<DesktopApp> {
+    let mut seen = HashSet::new();
+    let mut apps = Vec::new();
+
+    for dir in application_dirs() {
+        let mut files = Vec::new();
+        desktop_files(&dir, "", &mut files);
+
+        for (id, path) in files {
+            // A hidden entry still shadows the same id in later directories
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            let Ok(contents) = fs::read_to_string(&path) else {
+                continue;
+            };
+            apps.extend(parse_desktop_file(id, &path, &contents));
+        }
+    }
+
+    apps.sort_by_key(|app| app.name.to_lowercase());
+    apps
+}
+
+/// How well `app` matches the lowercase `query`, lower is better
+fn match_rank(app: &DesktopApp, query: &str) -> Option<u8> {
+    let name = app.name.to_lowercase();
+    if name.starts_with(query) {
+        return Some(0);
+    }
+    if name.contains(query) {
+        return Some(1);
+    }
+
+    let others = [&app.id, &app.exec]
+        .into_iter()
+        .chain(&app.generic_name)
+        .chain(&app.startup_wm_class)
+        .chain(&app.keywords)
+        .chain(&app.categories);
+    for field in others {
+        if field.to_lowercase().contains(query) {
+            return Some(2);
+        }
+    }
+    None
+}
+
+/// Tauri command searching installed applications by name, id, command, window
+/// class, keywords and categories. An empty query returns every application.
+#[tauri::command]
+pub fn search_applications(
+    query: Option<String>,
+    include_hidden: Option<bool>,
+) -> Result<Vec<DesktopApp>, BackendError> {
+    let query = query.unwrap_or_default().trim().to_lowercase();
+    let include_hidden = include_hidden.unwrap_or(false);
+
+    let mut matches: Vec<(u8, DesktopApp)> = load_applications()
+        .into_iter()
+        .filter(|app| include_hidden || !app.no_display)
+        .filter_map(|app| match_rank(&app, &query).map(|rank| (rank, app)))
+        .collect();
+
+    // The sort is stable, so apps stay sorted by name within a rank
+    matches.sort_by_key(|(rank, _)| *rank);
+
+    let mut resolver = IconResolver::for_current_theme();
+    Ok(matches
+        .into_iter()
+        .map(|(_, mut app)| {
+            app.icon_path = app
+                .icon
+                .as_deref()
+                .and_then(|icon| resolver.find(icon, DEFAULT_ICON_SIZE, 1))
+                .map(|path| path.display().to_string());
+            app
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(contents: &str) -> Option<DesktopApp> {
+        parse_desktop_file("test".to_string(), Path::new("/test.desktop"), contents)
+    }
+
+    #[test]
+    fn parses_an_application() {
+        let app = parse(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=Files\n\
+             Name[de]=Dateien\n\
+             # A comment\n\
+             Comment=Browse\\sfiles\n\
+             Exec=nautilus --new-window %U\n\
+             Icon=org.gnome.Nautilus\n\
+             Categories=GNOME;Utility;\n\
+             Keywords=folder;manager\n\
+             StartupWMClass=org.gnome.Nautilus\n\
+             Terminal=false\n\
+             \n\
+             [Desktop Action new-window]\n\
+             Name=New Window\n\
+             Exec=nautilus --other\n",
+        )
+        .unwrap();
+
+        assert_eq!(app.name, "Files");
+        assert_eq!(app.comment.as_deref(), Some("Browse files"));
+        assert_eq!(app.exec, "nautilus --new-window");
+        assert_eq!(app.categories, ["GNOME", "Utility"]);
+        assert_eq!(app.keywords, ["folder", "manager"]);
+        assert_eq!(app.startup_wm_class.as_deref(), Some("org.gnome.Nautilus"));
+        assert!(!app.terminal);
+        assert!(!app.no_display);
+    }
+
+    #[test]
+    fn skips_entries_that_cannot_be_launched() {
+        // Not an application
+        assert!(
+            parse("[Desktop Entry]\nType=Link\nName=Docs\nURL=https://example.com\n").is_none()
+        );
+        // Hidden
+        assert!(
+            parse("[Desktop Entry]\nType=Application\nName=A\nExec=a\nHidden=true\n").is_none()
+        );
+        // No Exec
+        assert!(parse("[Desktop Entry]\nType=Application\nName=A\n").is_none());
+        // Keys outside the [Desktop Entry] group
+        assert!(parse("[Other]\nType=Application\nName=A\nExec=a\n").is_none());
+        // Garbage
+        assert!(parse("not a desktop file\n=\n[\n").is_none());
+    }
+
+    #[test]
+    fn strips_field_codes() {
+        assert_eq!(strip_field_codes("firefox %u"), "firefox");
+        assert_eq!(
+            strip_field_codes("gimp-2.10 %U --no-splash"),
+            "gimp-2.10 --no-splash"
+        );
+        assert_eq!(strip_field_codes("app --icon %i %c %k"), "app --icon");
+        assert_eq!(strip_field_codes("date +%%H"), "date +%H");
+        assert_eq!(
+            strip_field_codes("flatpak run --file-forwarding org.app.App @@u %U @@"),
+            "flatpak run --file-forwarding org.app.App"
+        );
+    }
+
+    #[test]
+    fn keeps_quoted_exec_arguments_together() {
+        assert_eq!(
+            strip_field_codes(r#"sh -c "echo 'hi there'; sleep 1" %f"#),
+            r#"sh -c 'echo '\''hi there'\''; sleep 1'"#
+        );
+        assert_eq!(strip_field_codes(r#"sh -c "foo %f""#), "sh -c 'foo '");
+        assert_eq!(
+            strip_field_codes(r#""/opt/My App/run" --flag"#),
+            "'/opt/My App/run' --flag"
+        );
+        assert_eq!(
+            strip_field_codes(r#"app "a \"quoted\" \$word""#),
+            r#"app 'a "quoted" $word'"#
+        );
+    }
+
+    #[test]
+    fn splits_exec_arguments() {
+        assert_eq!(split_exec("  a   b  "), ["a", "b"]);
+        assert_eq!(split_exec(r#"a "" b"#), ["a", "", "b"]);
+        assert_eq!(split_exec(r#"a"b c"d"#), ["ab cd"]);
+        // An unterminated quote runs to the end
+        assert_eq!(split_exec(r#"a "b c"#), ["a", "b c"]);
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::Path;
+
+use crate::backend_error::BackendError;
+
+pub fn content_hash(contents: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of the file's current content, `None` when it can't be read
+pub fn file_hash(path: &Path) -> Option<u64> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|contents| content_hash(&contents))
+}
+
+/// Write `contents` through a temp file in the same directory and rename it into place,
+/// so readers (and Hyprland's own reload) never see a half-written file
+pub fn write_atomic(path: &Path, contents: &str) -> Result<(), BackendError> {
+    write_atomic_checked(path, contents, file_hash(path))
+}
+
+/// Like `write_atomic`, but refuses with a conflict error when the file no longer hashes
+/// to `expected_hash` right before it is replaced. `None` expects the file to be missing.
+pub fn write_atomic_checked(
+    path: &Path,
+    contents: &str,
+    expected_hash: Option<u64>,
+) -> Result<(), BackendError> {
+    // Write through symlinks (e.g. dotfile managers) instead of replacing the link
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = target.parent().unwrap_or(Path::new("/"));
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| BackendError::invalid_input(format!("Invalid file path {:?}", path)))?
+        .to_string_lossy();
+    let temp_path = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+    let result = write_temp(&temp_path, &target, contents).and_then(|_| {
+        if file_hash(&target) != expected_hash {
+            return Err(BackendError::conflict(path));
+        }
+
+        fs::rename(&temp_path, &target).map_err(|e| BackendError::io("replace", path, e))
+    });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+fn write_temp(temp_path: &Path, target: &Path, contents: &str) -> Result<(), BackendError> {
+    let mut file =
+        fs::File::create(temp_path).map_err(|e| BackendError::io("create", temp_path, e))?;
+    file.write_all(contents.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| BackendError::io("write", temp_path, e))?;
+
+    // Keep the permissions of the file being replaced
+    if let Ok(metadata) = fs::metadata(target) {
+        fs::set_permissions(temp_path, metadata.permissions())
+            .map_err(|e| BackendError::io("set permissions on", temp_path, e))?;
+    }
+
+    Ok(())
+}
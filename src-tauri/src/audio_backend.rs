@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::process::Command;
 
+use crate::app_catalog::load_applications;
+use crate::backend_error::{BackendError, ErrorCode};
+use crate::icon_theme::{DEFAULT_ICON_SIZE, IconResolver, app_icon};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AudioDevice {
     pub id: u32,
@@ -19,6 +23,8 @@ pub struct AudioStream {
     pub media_name: Option<String>,
     pub volume: f32,
     pub muted: bool,
+    /// Icon file for `app_name`, servable through the asset protocol
+    pub icon: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -29,31 +35,32 @@ pub struct AudioState {
 }
 
 /// Check if wpctl is available on the system
-fn check_wpctl_available() -> Result<(), String> {
+fn check_wpctl_available() -> Result<(), BackendError> {
     Command::new("which")
         .arg("wpctl")
         .output()
-        .map_err(|e| format!("Failed to check for wpctl: {}", e))
+        .map_err(|e| BackendError::spawn_failed("which", e))
         .and_then(|output| {
             if output.status.success() {
                 Ok(())
             } else {
-                Err("wpctl not found. Please install WirePlumber to manage audio.".to_string())
+                Err(BackendError::new(ErrorCode::ToolMissing, "wpctl not found")
+                    .with_hint("Install WirePlumber to manage audio"))
             }
         })
 }
 
 /// Get volume for a specific node ID
-fn get_node_volume(node_id: u32) -> Result<(f32, bool), String> {
+fn get_node_volume(node_id: u32) -> Result<(f32, bool), BackendError> {
     let output = Command::new("wpctl")
         .args(["get-volume", &node_id.to_string()])
         .output()
-        .map_err(|e| format!("Failed to get volume for node {}: {}", node_id, e))?;
+        .map_err(|e| BackendError::spawn_failed("wpctl", e))?;
 
     if !output.status.success() {
-        return Err(format!(
-            "wpctl get-volume failed: {}",
-            String::from_utf8_lossy(&output.stderr)
+        return Err(BackendError::command_failed(
+            "wpctl get-volume",
+            &output.stderr,
         ));
     }
 
@@ -136,6 +143,7 @@ fn parse_stream_line(line: &str) -> Option<AudioStream> {
         media_name: media_name.map(|s| s.to_string()),
         volume: 0.0, // Will be filled later
         muted: false,
+        icon: None,
     })
 }
 
@@ -170,6 +178,7 @@ fn get_pactl_streams() -> Vec<AudioStream> {
                         media_name: None,
                         volume: 1.0,
                         muted: false,
+                        icon: None,
                     });
                 }
             }
@@ -310,6 +319,7 @@ fn get_pw_dump_streams(sink_ids: &[u32], source_ids: &[u32]) -> Vec<AudioStream>
                 media_name,
                 volume,
                 muted,
+                icon: None,
             });
         }
     }
@@ -318,19 +328,16 @@ fn get_pw_dump_streams(sink_ids: &[u32], source_ids: &[u32]) -> Vec<AudioStream>
 }
 
 #[tauri::command]
-pub fn get_audio_state() -> Result<AudioState, String> {
+pub fn get_audio_state() -> Result<AudioState, BackendError> {
     check_wpctl_available()?;
 
     let output = Command::new("wpctl")
         .arg("status")
         .output()
-        .map_err(|e| format!("Failed to run wpctl status: {}", e))?;
+        .map_err(|e| BackendError::spawn_failed("wpctl", e))?;
 
     if !output.status.success() {
-        return Err(format!(
-            "wpctl status failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+        return Err(BackendError::command_failed("wpctl status", &output.stderr));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -454,6 +461,14 @@ pub fn get_audio_state() -> Result<AudioState, String> {
         }
     }
 
+    if !streams.is_empty() {
+        let mut resolver = IconResolver::for_current_theme();
+        let apps = load_applications();
+        for stream in &mut streams {
+            stream.icon = app_icon(&mut resolver, &apps, &stream.app_name, DEFAULT_ICON_SIZE, 1);
+        }
+    }
+
     Ok(AudioState {
         sinks,
         sources,
@@ -462,26 +477,26 @@ pub fn get_audio_state() -> Result<AudioState, String> {
 }
 
 #[tauri::command]
-pub fn set_default_device(device_id: u32) -> Result<(), String> {
+pub fn set_default_device(device_id: u32) -> Result<(), BackendError> {
     check_wpctl_available()?;
 
     let output = Command::new("wpctl")
         .args(["set-default", &device_id.to_string()])
         .output()
-        .map_err(|e| format!("Failed to set default device: {}", e))?;
+        .map_err(|e| BackendError::spawn_failed("wpctl", e))?;
 
     if output.status.success() {
         Ok(())
     } else {
-        Err(format!(
-            "Failed to set default device: {}",
-            String::from_utf8_lossy(&output.stderr)
+        Err(BackendError::command_failed(
+            "wpctl set-default",
+            &output.stderr,
         ))
     }
 }
 
 #[tauri::command]
-pub fn set_volume(node_id: u32, volume: f32) -> Result<(), String> {
+pub fn set_volume(node_id: u32, volume: f32) -> Result<(), BackendError> {
     // Clamp volume to reasonable range (0.0 - 1.5 for 150% boost)
     let vol = volume.clamp(0.0, 1.5);
 
@@ -508,16 +523,16 @@ pub fn set_volume(node_id: u32, volume: f32) -> Result<(), String> {
 
     match pactl_result {
         Ok(output) if output.status.success() => Ok(()),
-        Ok(output) => Err(format!(
-            "Failed to set volume: {}",
-            String::from_utf8_lossy(&output.stderr)
+        Ok(output) => Err(BackendError::command_failed(
+            "pactl set-sink-input-volume",
+            &output.stderr,
         )),
-        Err(e) => Err(format!("Failed to set volume: {}", e)),
+        Err(e) => Err(BackendError::spawn_failed("pactl", e)),
     }
 }
 
 #[tauri::command]
-pub fn set_mute(node_id: u32, muted: bool) -> Result<(), String> {
+pub fn set_mute(node_id: u32, muted: bool) -> Result<(), BackendError> {
     let mute_val = if muted { "1" } else { "0" };
 
     // Try wpctl first
@@ -539,10 +554,10 @@ pub fn set_mute(node_id: u32, muted: bool) -> Result<(), String> {
 
     match pactl_result {
         Ok(output) if output.status.success() => Ok(()),
-        Ok(output) => Err(format!(
-            "Failed to set mute state: {}",
-            String::from_utf8_lossy(&output.stderr)
+        Ok(output) => Err(BackendError::command_failed(
+            "pactl set-sink-input-mute",
+            &output.stderr,
         )),
-        Err(e) => Err(format!("Failed to set mute: {}", e)),
+        Err(e) => Err(BackendError::spawn_failed("pactl", e)),
     }
 }
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// Stable error codes the frontend can branch on
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// A config file the command needs does not exist
+    ConfigNotFound,
+    /// A config file exists but could not be parsed
+    ParseFailed,
+    /// A file was edited outside of hyprconfig since it was read
+    Conflict,
+    /// The arguments of the command were rejected
+    InvalidInput,
+    /// The entry the command refers to does not exist
+    NotFound,
+    /// Reading or writing a file failed
+    Io,
+    /// An external program the command relies on is not installed
+    ToolMissing,
+    /// An external program ran but reported a failure
+    CommandFailed,
+    /// Hyprland's IPC socket is not reachable
+    HyprlandNotRunning,
+    Other,
+}
+
+/// Error returned by every Tauri command, serialized as-is to the frontend
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackendError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    /// Underlying error as reported by the library, program or OS
+    pub cause: Option<String>,
+    /// What the user can do about it
+    pub hint: Option<String>,
+}
+
+impl BackendError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        BackendError {
+            code,
+            message: message.into(),
+            file: None,
+            line: None,
+            cause: None,
+            hint: None,
+        }
+    }
+
+    pub fn with_file(mut self, path: &Path) -> Self {
+        self.file = Some(path.display().to_string());
+        self
+    }
+
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn with_cause(mut self, cause: impl fmt::Display) -> Self {
+        self.cause = Some(cause.to_string());
+        self
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn config_not_found(path: &Path) -> Self {
+        Self::new(
+            ErrorCode::ConfigNotFound,
+            format!("Config file not found at {:?}", path),
+        )
+        .with_file(path)
+    }
+
+    pub fn parse_failed(path: &Path, cause: impl fmt::Debug) -> Self {
+        Self::new(
+            ErrorCode::ParseFailed,
+            format!("Failed to parse {:?}", path),
+        )
+        .with_file(path)
+        .with_cause(format!("{:?}", cause))
+    }
+
+    pub fn conflict(path: &Path) -> Self {
+        Self::new(
+            ErrorCode::Conflict,
+            format!(
+                "{:?} was modified outside of hyprconfig since it was loaded",
+                path
+            ),
+        )
+        .with_file(path)
+        .with_hint("Reload and try again")
+    }
+
+    /// A file operation failed; `action` reads like "Failed to <action> <path>"
+    pub fn io(action: &str, path: &Path, cause: io::Error) -> Self {
+        Self::new(ErrorCode::Io, format!("Failed to {} {:?}", action, path))
+            .with_file(path)
+            .with_cause(cause)
+    }
+
+    /// Spawning `program` failed, which usually means it is not installed
+    pub fn spawn_failed(program: &str, cause: io::Error) -> Self {
+        if cause.kind() == io::ErrorKind::NotFound {
+            Self::new(
+                ErrorCode::ToolMissing,
+                format!("{} is not installed", program),
+            )
+            .with_hint(format!(
+                "Install {} and make sure it is in your PATH",
+                program
+            ))
+        } else {
+            Self::new(
+                ErrorCode::CommandFailed,
+                format!("Failed to run {}", program),
+            )
+            .with_cause(cause)
+        }
+    }
+
+    /// `program` ran but exited with an error
+    pub fn command_failed(program: &str, stderr: &[u8]) -> Self {
+        Self::new(ErrorCode::CommandFailed, format!("{} failed", program))
+            .with_cause(String::from_utf8_lossy(stderr).trim().to_string())
+    }
+
+    pub fn hyprland_not_running(cause: impl fmt::Display) -> Self {
+        Self::new(ErrorCode::HyprlandNotRunning, "Could not reach Hyprland")
+            .with_cause(cause)
+            .with_hint("Make sure Hyprland is running and accessible")
+    }
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(cause) = &self.cause {
+            write!(f, ": {}", cause)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for BackendError {}
+
+/// Plain messages, e.g. from `format!`, carry no code of their own
+impl From<String> for BackendError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::Other, message)
+    }
+}
+
+impl From<&str> for BackendError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorCode::Other, message)
+    }
+}
@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+use crate::atomic_write::write_atomic;
+use crate::backend_error::{BackendError, ErrorCode};
+use crate::config_backend::{get_backup_preferences, get_hyprconfig_dir};
+use crate::config_session::ConfigSession;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// A single file captured in a backup
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupFile {
+    pub path: String,
+    /// False when the file did not exist yet, restoring removes it again
+    pub existed: bool,
+}
+
+/// Metadata of a backup, stored as manifest.json inside the backup folder
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupInfo {
+    pub id: String,
+    pub created_at: u64,
+    pub reason: String,
+    pub files: Vec<BackupFile>,
+}
+
+/// Content of a file before and after a write; `None` means the file did not exist
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// In-memory contents of a set of files, captured before a write that may touch them
+pub struct FileSnapshot {
+    files: Vec<(PathBuf, Option<String>)>,
+}
+
+impl FileSnapshot {
+    pub fn capture(paths: &[PathBuf]) -> Self {
+        FileSnapshot {
+            files: paths
+                .iter()
+                .map(|path| (path.clone(), fs::read_to_string(path).ok()))
+                .collect(),
+        }
+    }
+
+    /// Store the captured contents in the backup folder
+    pub fn back_up(&self, reason: &str) -> Result<(), BackendError> {
+        write_backup(reason, self.files.clone())
+    }
+
+    /// Files whose content on disk no longer matches the snapshot
+    pub fn changes(&self) -> Vec<FileChange> {
+        self.files
+            .iter()
+            .filter_map(|(path, before)| {
+                let after = fs::read_to_string(path).ok();
+                (after != *before).then(|| FileChange {
+                    path: path.clone(),
+                    before: before.clone(),
+                    after,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Get the backup directory ($XDG_CONFIG_HOME/hyprconfig/.backup)
+fn get_backup_root() -> Result<PathBuf, BackendError> {
+    let backup_root = get_hyprconfig_dir()?.join(".backup");
+
+    if !backup_root.exists() {
+        fs::create_dir_all(&backup_root)
+            .map_err(|e| BackendError::io("create", &backup_root, e))?;
+    }
+
+    Ok(backup_root)
+}
+
+fn now_unix() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Snapshot the given files into the backup folder before they are overwritten
+pub fn backup_before_write(reason: &str, paths: &[&Path]) -> Result<(), BackendError> {
+    let files = paths
+        .iter()
+        .map(|path| (path.to_path_buf(), fs::read_to_string(path).ok()))
+        .collect();
+
+    write_backup(reason, files)
+}
+
+fn write_backup(reason: &str, files: Vec<(PathBuf, Option<String>)>) -> Result<(), BackendError> {
+    let preferences = get_backup_preferences();
+
+    if !preferences.enable || files.is_empty() {
+        return Ok(());
+    }
+
+    let backup_root = get_backup_root()?;
+    let now = now_unix();
+
+    // Millisecond ids keep backups sorted on disk; bump on collision
+    let mut id_millis = now.as_millis();
+    while backup_root.join(id_millis.to_string()).exists() {
+        id_millis += 1;
+    }
+    let id = id_millis.to_string();
+    let backup_dir = backup_root.join(&id);
+
+    fs::create_dir_all(&backup_dir).map_err(|e| BackendError::io("create", &backup_dir, e))?;
+
+    let mut manifest_files = Vec::new();
+
+    for (index, (path, contents)) in files.iter().enumerate() {
+        if let Some(contents) = contents {
+            let backup_path = backup_dir.join(index.to_string());
+            fs::write(&backup_path, contents)
+                .map_err(|e| BackendError::io("back up to", &backup_path, e).with_file(path))?;
+        }
+
+        manifest_files.push(BackupFile {
+            path: path.display().to_string(),
+            existed: contents.is_some(),
+        });
+    }
+
+    let info = BackupInfo {
+        id,
+        created_at: now.as_secs(),
+        reason: reason.to_string(),
+        files: manifest_files,
+    };
+
+    let manifest_path = backup_dir.join(MANIFEST_FILE);
+    let manifest = serde_json::to_string_pretty(&info).map_err(|e| {
+        BackendError::new(ErrorCode::Io, "Failed to serialize backup manifest")
+            .with_file(&manifest_path)
+            .with_cause(e)
+    })?;
+    fs::write(&manifest_path, manifest)
+        .map_err(|e| BackendError::io("write", &manifest_path, e))?;
+
+    apply_retention(
+        &backup_root,
+        preferences.max_backups,
+        preferences.max_age_days,
+    );
+
+    Ok(())
+}
+
+/// Read all backups, newest first
+fn read_backups(backup_root: &Path) -> Vec<BackupInfo> {
+    let mut backups: Vec<BackupInfo> = fs::read_dir(backup_root)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| fs::read_to_string(entry.path().join(MANIFEST_FILE)).ok())
+                .filter_map(|manifest| serde_json::from_str(&manifest).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    backups
+}
+
+/// Remove backups beyond the configured count or older than the configured age.
+/// The newest backup is always kept.
+fn apply_retention(backup_root: &Path, max_backups: usize, max_age_days: u64) {
+    let cutoff = now_unix()
+        .as_secs()
+        .saturating_sub(max_age_days * 24 * 60 * 60);
+
+    for (position, backup) in read_backups(backup_root).iter().enumerate() {
+        let too_many = position >= max_backups;
+        let too_old = position > 0 && max_age_days > 0 && backup.created_at < cutoff;
+
+        if (too_many || too_old)
+            && let Err(e) = fs::remove_dir_all(backup_root.join(&backup.id))
+        {
+            eprintln!("Failed to remove old backup {}: {}", backup.id, e);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn list_backups() -> Result<Vec<BackupInfo>, BackendError> {
+    Ok(read_backups(&get_backup_root()?))
+}
+
+#[tauri::command]
+pub fn restore_backup(session: State<'_, ConfigSession>, id: String) -> Result<(), BackendError> {
+    // Ids are plain millisecond timestamps, never paths
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(BackendError::invalid_input(format!(
+            "Invalid backup id '{}'",
+            id
+        )));
+    }
+
+    let backup_dir = get_backup_root()?.join(&id);
+    let manifest_path = backup_dir.join(MANIFEST_FILE);
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|e| BackendError::not_found(format!("Backup '{}' not found", id)).with_cause(e))?;
+    let info: BackupInfo = serde_json::from_str(&manifest)
+        .map_err(|e| BackendError::parse_failed(&manifest_path, e))?;
+
+    // Read everything up front so a damaged backup doesn't leave a half-restored config
+    let mut restores = Vec::new();
+    for (index, file) in info.files.iter().enumerate() {
+        let contents = if file.existed {
+            Some(
+                fs::read_to_string(backup_dir.join(index.to_string()))
+                    .map_err(|e| BackendError::io("read backed up", Path::new(&file.path), e))?,
+            )
+        } else {
+            None
+        };
+        restores.push((PathBuf::from(&file.path), contents));
+    }
+
+    // The session lock keeps commands from reading or saving the config meanwhile, and
+    // the parsed config is dropped afterwards, even after a partial restore
+    session.replace_files(|| {
+        // Restoring is itself a write, so keep the current state restorable too
+        let paths: Vec<&Path> = restores.iter().map(|(path, _)| path.as_path()).collect();
+        backup_before_write(&format!("Before restoring backup {}", id), &paths)?;
+
+        restores
+            .into_iter()
+            .try_for_each(|(path, contents)| match contents {
+                Some(contents) => {
+                    if let Some(parent) = path.parent() {
+                        fs::create_dir_all(parent)
+                            .map_err(|e| BackendError::io("create", parent, e))?;
+                    }
+                    write_atomic(&path, &contents)
+                }
+                None => {
+                    if path.exists() {
+                        fs::remove_file(&path).map_err(|e| BackendError::io("remove", &path, e))?;
+                    }
+                    Ok(())
+                }
+            })
+    })
+}
@@ -0,0 +1,506 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::backend_error::BackendError;
+use crate::config_session::{ConfigSession, MutationResult};
+use crate::journal_backend::Journal;
+use crate::options_backend::{
+    OptionInfo, OptionValue, option_info, scan_all_options, write_option,
+};
+
+/// Hyprland accepts at most this many colors in a gradient
+const MAX_GRADIENT_STOPS: usize = 10;
+
+/// Options the Border Colors page edits
+const BORDER_COLOR_KEYS: &[&str] = &[
+    "general:col.active_border",
+    "general:col.inactive_border",
+    "general:col.nogroup_border",
+    "general:col.nogroup_border_active",
+    "group:col.border_active",
+    "group:col.border_inactive",
+    "group:col.border_locked_active",
+    "group:col.border_locked_inactive",
+    "group:groupbar:col.active",
+    "group:groupbar:col.inactive",
+    "group:groupbar:col.locked_active",
+    "group:groupbar:col.locked_inactive",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// How a color is written in the config
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorFormat {
+    /// `rgba(RRGGBBAA)`
+    Rgba,
+    /// `rgb(RRGGBB)`, only for opaque colors
+    Rgb,
+    /// `0xAARRGGBB`
+    Hex,
+}
+
+impl Color {
+    pub fn from_argb(argb: u32) -> Self {
+        let [a, r, g, b] = argb.to_be_bytes();
+        Color { r, g, b, a }
+    }
+
+    pub fn argb(&self) -> u32 {
+        u32::from_be_bytes([self.a, self.r, self.g, self.b])
+    }
+
+    /// Render the color; `Rgb` falls back to `Rgba` for translucent colors
+    pub fn format(&self, format: ColorFormat) -> String {
+        let Color { r, g, b, a } = *self;
+        match format {
+            ColorFormat::Rgb if a == 0xff => format!("rgb({:02x}{:02x}{:02x})", r, g, b),
+            ColorFormat::Rgb | ColorFormat::Rgba => {
+                format!("rgba({:02x}{:02x}{:02x}{:02x})", r, g, b, a)
+            }
+            ColorFormat::Hex => format!("0x{:08x}", self.argb()),
+        }
+    }
+}
+
+/// Split on whitespace outside of parentheses, so `rgba(1, 2, 3, 1)` stays one token
+pub fn tokenize(value: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut depth = 0;
+    let mut start = None;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(s) = start.take() {
+                    tokens.push(&value[s..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+
+    if let Some(s) = start {
+        tokens.push(&value[s..]);
+    }
+    tokens
+}
+
+fn parse_hex(digits: &str, len: usize) -> Option<u32> {
+    if digits.len() != len || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(digits, 16).ok()
+}
+
+/// Parse the decimal form `r, g, b` or `r, g, b, a` with alpha between 0 and 1
+fn parse_decimal(args: &str, with_alpha: bool) -> Option<Color> {
+    let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+    let channel = |i: usize| parts.get(i)?.parse::<u8>().ok();
+
+    let a = match (with_alpha, parts.len()) {
+        (false, 3) => 0xff,
+        (true, 4) => {
+            let alpha = parts[3]
+                .parse::<f64>()
+                .ok()
+                .filter(|a| (0.0..=1.0).contains(a))?;
+            (alpha * 255.0).round() as u8
+        }
+        _ => return None,
+    };
+
+    Some(Color {
+        r: channel(0)?,
+        g: channel(1)?,
+        b: channel(2)?,
+        a,
+    })
+}
+
+/// Parse one color in any syntax Hyprland accepts
+pub fn parse_color(token: &str) -> Option<(Color, ColorFormat)> {
+    let token = token.trim();
+
+    if let Some(digits) = token.strip_prefix("0x") {
+        return parse_hex(digits, 8).map(|argb| (Color::from_argb(argb), ColorFormat::Hex));
+    }
+
+    if let Some(args) = token
+        .strip_prefix("rgba(")
+        .and_then(|t| t.strip_suffix(')'))
+    {
+        let color = match parse_hex(args.trim(), 8) {
+            // RRGGBBAA -> AARRGGBB
+            Some(rgba) => Color::from_argb(rgba.rotate_right(8)),
+            None => parse_decimal(args, true)?,
+        };
+        return Some((color, ColorFormat::Rgba));
+    }
+
+    if let Some(args) = token.strip_prefix("rgb(").and_then(|t| t.strip_suffix(')')) {
+        let color = match parse_hex(args.trim(), 6) {
+            Some(rgb) => Color::from_argb(0xff00_0000 | rgb),
+            None => parse_decimal(args, false)?,
+        };
+        return Some((color, ColorFormat::Rgb));
+    }
+
+    None
+}
+
+pub fn is_color(token: &str) -> bool {
+    parse_color(token).is_some()
+}
+
+/// Add the `0x` prefix to the bare `AARRGGBB` tokens Hyprland reports for gradients
+/// at runtime, which neither the config nor `hyprctl keyword` accept without it
+pub fn prefix_runtime_colors(value: &str) -> String {
+    value
+        .split_whitespace()
+        .map(|token| {
+            if token.len() == 8 && token.chars().all(|c| c.is_ascii_hexdigit()) {
+                format!("0x{}", token)
+            } else {
+                token.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// One color of a gradient
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ColorStop {
+    Literal {
+        color: Color,
+        format: ColorFormat,
+    },
+    /// A `$variable`, with its color when its value is a single color
+    Variable {
+        name: String,
+        color: Option<Color>,
+    },
+}
+
+/// A color option value: one or more stops and an optional angle in degrees
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Gradient {
+    pub stops: Vec<ColorStop>,
+    pub angle: Option<f64>,
+}
+
+/// Parse a gradient angle such as `45deg`
+pub fn parse_angle(token: &str) -> Option<f64> {
+    token.strip_suffix("deg")?.parse().ok()
+}
+
+/// Parse a color or gradient value as written, e.g. `$accent rgba(00ff99ee) 45deg`
+pub fn parse_gradient(value: &str, variables: &HashMap<String, String>) -> Option<Gradient> {
+    let mut tokens = tokenize(value.trim());
+    let angle = tokens.last().and_then(|t| parse_angle(t));
+    if angle.is_some() {
+        tokens.pop();
+    }
+
+    let stops = tokens
+        .iter()
+        .map(|token| match token.strip_prefix('$') {
+            Some(name) => Some(ColorStop::Variable {
+                name: name.to_string(),
+                color: variables
+                    .get(name)
+                    .and_then(|v| parse_color(v))
+                    .map(|(color, _)| color),
+            }),
+            None => parse_color(token).map(|(color, format)| ColorStop::Literal { color, format }),
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    (!stops.is_empty()).then_some(Gradient { stops, angle })
+}
+
+/// Render a gradient the way it is written in hyprland.conf
+pub fn format_gradient(gradient: &Gradient) -> Result<String, BackendError> {
+    if gradient.stops.is_empty() {
+        return Err(BackendError::invalid_input(
+            "A color needs at least one stop",
+        ));
+    }
+    if gradient.stops.len() > MAX_GRADIENT_STOPS {
+        return Err(BackendError::invalid_input(format!(
+            "A gradient can have at most {} colors",
+            MAX_GRADIENT_STOPS
+        )));
+    }
+
+    let mut parts = Vec::new();
+    for stop in &gradient.stops {
+        parts.push(match stop {
+            ColorStop::Literal { color, format } => color.format(*format),
+            ColorStop::Variable { name, .. } => {
+                if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    return Err(BackendError::invalid_input(format!(
+                        "'${}' is not a valid variable",
+                        name
+                    )));
+                }
+                format!("${}", name)
+            }
+        });
+    }
+    if let Some(angle) = gradient.angle {
+        parts.push(format!("{}deg", angle));
+    }
+
+    Ok(parts.join(" "))
+}
+
+/// Whether a variable holds a color or gradient, with every variable it uses resolved
+pub fn is_color_value(value: &str, variables: &HashMap<String, String>) -> bool {
+    parse_gradient(value, variables).is_some_and(|gradient| {
+        gradient.stops.iter().all(|stop| match stop {
+            ColorStop::Literal { .. } => true,
+            ColorStop::Variable { color, .. } => color.is_some(),
+        })
+    })
+}
+
+fn is_color_key(key: &str) -> bool {
+    key.rsplit(':')
+        .next()
+        .is_some_and(|name| name.starts_with("col."))
+}
+
+/// A color option with its value parsed as written, `$variables` kept
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColorOption {
+    pub key: String,
+    /// `None` when the option isn't set or isn't a valid color
+    pub gradient: Option<Gradient>,
+    pub config: OptionInfo,
+}
+
+/// Tauri command returning the border and group colors
+#[tauri::command]
+pub fn get_border_colors(
+    session: State<'_, ConfigSession>,
+) -> Result<Vec<ColorOption>, BackendError> {
+    session.read(|hypr, config_path| {
+        let mut found = scan_all_options(config_path);
+        let variables = hypr.config().variables();
+
+        Ok(BORDER_COLOR_KEYS
+            .iter()
+            .map(|key| {
+                let config = option_info(hypr, config_path, key, found.remove(*key));
+                ColorOption {
+                    key: key.to_string(),
+                    gradient: config
+                        .raw
+                        .as_deref()
+                        .and_then(|raw| parse_gradient(raw, variables)),
+                    config,
+                }
+            })
+            .collect())
+    })
+}
+
+/// Tauri command setting a `col.*` option to a color or gradient
+#[tauri::command]
+pub fn set_color_option(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    key: String,
+    gradient: Gradient,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    if !is_color_key(&key) {
+        return Err(BackendError::invalid_input(format!(
+            "'{}' is not a color option",
+            key
+        )));
+    }
+
+    let formatted = format_gradient(&gradient)?;
+    let value = match (gradient.stops.len(), gradient.angle) {
+        (1, None) => OptionValue::Color(formatted),
+        _ => OptionValue::String(formatted),
+    };
+
+    write_option(&session, &journal, key, &value, target, dry_run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color { r, g, b, a }
+    }
+
+    #[test]
+    fn tokenize_keeps_parentheses_together() {
+        assert_eq!(
+            tokenize("rgba(1, 2, 3, 0.5)  $accent\t45deg"),
+            ["rgba(1, 2, 3, 0.5)", "$accent", "45deg"]
+        );
+        assert!(tokenize("   ").is_empty());
+    }
+
+    #[test]
+    fn parses_hex_forms() {
+        assert_eq!(
+            parse_color("0xee33ccff"),
+            Some((rgba(0x33, 0xcc, 0xff, 0xee), ColorFormat::Hex))
+        );
+        assert_eq!(
+            parse_color("rgba(33ccffee)"),
+            Some((rgba(0x33, 0xcc, 0xff, 0xee), ColorFormat::Rgba))
+        );
+        assert_eq!(
+            parse_color("rgb(595959)"),
+            Some((rgba(0x59, 0x59, 0x59, 0xff), ColorFormat::Rgb))
+        );
+    }
+
+    #[test]
+    fn parses_decimal_forms() {
+        assert_eq!(
+            parse_color("rgb(255, 0, 10)"),
+            Some((rgba(255, 0, 10, 0xff), ColorFormat::Rgb))
+        );
+        assert_eq!(
+            parse_color("rgba(255,0,10,0.5)"),
+            Some((rgba(255, 0, 10, 128), ColorFormat::Rgba))
+        );
+        assert_eq!(parse_decimal("1, 2, 3", false), Some(rgba(1, 2, 3, 0xff)));
+        assert_eq!(parse_decimal("1, 2, 3, 1", true), Some(rgba(1, 2, 3, 0xff)));
+    }
+
+    #[test]
+    fn rejects_malformed_colors() {
+        for token in [
+            "",
+            "0x",
+            "0x33ccff",
+            "0xgg33ccff",
+            "rgba(33ccff)",
+            "rgba(33ccffee",
+            "rgb(33ccffee)",
+            "rgb(256, 0, 0)",
+            "rgb(1, 2)",
+            "rgb(1, 2, 3, 0.5)",
+            "rgba(1, 2, 3, 1.5)",
+            "rgba(1, 2, 3)",
+            "#33ccff",
+            "red",
+        ] {
+            assert_eq!(parse_color(token), None, "{}", token);
+        }
+        assert_eq!(parse_decimal("1, 2, x", false), None);
+        assert_eq!(parse_decimal("1, 2, 3, -0.1", true), None);
+    }
+
+    #[test]
+    fn prefixes_runtime_colors() {
+        assert_eq!(
+            prefix_runtime_colors("ee33ccff ee00ff99 45deg"),
+            "0xee33ccff 0xee00ff99 45deg"
+        );
+        assert_eq!(prefix_runtime_colors("0xff000000"), "0xff000000");
+        assert_eq!(
+            prefix_runtime_colors("rgb(ffffff) deadbeefs"),
+            "rgb(ffffff) deadbeefs"
+        );
+        assert!(is_color(&prefix_runtime_colors("ff1a1a1a")));
+    }
+
+    #[test]
+    fn formats_colors() {
+        let color = rgba(0x33, 0xcc, 0xff, 0xee);
+        assert_eq!(color.format(ColorFormat::Rgba), "rgba(33ccffee)");
+        assert_eq!(color.format(ColorFormat::Hex), "0xee33ccff");
+        // Translucent colors can't be written as rgb()
+        assert_eq!(color.format(ColorFormat::Rgb), "rgba(33ccffee)");
+        assert_eq!(rgba(1, 2, 3, 0xff).format(ColorFormat::Rgb), "rgb(010203)");
+    }
+
+    #[test]
+    fn parses_gradients_with_angles() {
+        let variables = HashMap::from([("accent".to_string(), "rgb(ff0000)".to_string())]);
+        let gradient = parse_gradient("rgba(33ccffee) $accent $unknown 45deg", &variables).unwrap();
+
+        assert_eq!(gradient.angle, Some(45.0));
+        assert_eq!(
+            gradient.stops,
+            [
+                ColorStop::Literal {
+                    color: rgba(0x33, 0xcc, 0xff, 0xee),
+                    format: ColorFormat::Rgba,
+                },
+                ColorStop::Variable {
+                    name: "accent".to_string(),
+                    color: Some(rgba(0xff, 0, 0, 0xff)),
+                },
+                ColorStop::Variable {
+                    name: "unknown".to_string(),
+                    color: None,
+                },
+            ]
+        );
+        assert_eq!(
+            format_gradient(&gradient).unwrap(),
+            "rgba(33ccffee) $accent $unknown 45deg"
+        );
+
+        let gradient = parse_gradient("0xff000000 rgb(1, 2, 3) -90.5deg", &variables).unwrap();
+        assert_eq!(gradient.angle, Some(-90.5));
+        assert_eq!(gradient.stops.len(), 2);
+    }
+
+    #[test]
+    fn rejects_malformed_gradients() {
+        let variables = HashMap::new();
+        assert_eq!(parse_gradient("", &variables), None);
+        assert_eq!(parse_gradient("45deg", &variables), None);
+        assert_eq!(parse_gradient("rgb(ffffff) nope", &variables), None);
+        assert_eq!(parse_gradient("rgb(ffffff) 45deg 90deg", &variables), None);
+
+        let too_many = Gradient {
+            stops: vec![
+                ColorStop::Literal {
+                    color: rgba(0, 0, 0, 0xff),
+                    format: ColorFormat::Rgb,
+                };
+                MAX_GRADIENT_STOPS + 1
+            ],
+            angle: None,
+        };
+        assert!(format_gradient(&too_many).is_err());
+
+        let bad_variable = Gradient {
+            stops: vec![ColorStop::Variable {
+                name: "a b".to_string(),
+                color: None,
+            }],
+            angle: None,
+        };
+        assert!(format_gradient(&bad_variable).is_err());
+    }
+}
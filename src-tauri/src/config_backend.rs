@@ -1,7 +1,15 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-use toml_edit::{value, DocumentMut};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use toml_edit::{DocumentMut, value};
+
+use crate::atomic_write::{file_hash, write_atomic_checked};
+use crate::backend_error::BackendError;
+use crate::config_paths::{home_dir, xdg_config_home};
+use crate::config_session::ConfigSession;
+use crate::config_targets::TargetCategory;
+use crate::journal_backend::{Journal, Subsystem};
 
 /// Matugen preferences structure
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -11,62 +19,132 @@ pub struct MatugenPreferences {
     pub generator_type: String,
 }
 
+/// Backup preferences structure
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupPreferences {
+    pub enable: bool,
+    pub max_backups: usize,
+    pub max_age_days: u64,
+}
+
+impl Default for BackupPreferences {
+    fn default() -> Self {
+        BackupPreferences {
+            enable: true,
+            max_backups: 50,
+            max_age_days: 30,
+        }
+    }
+}
+
+/// Validation preferences structure
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ValidationPreferences {
+    /// Revert a save right away when Hyprland reports new config errors after it
+    pub auto_rollback: bool,
+}
+
+/// Config path overrides, `None` lets the resolver pick the path
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PathPreferences {
+    pub hyprland_config: Option<String>,
+    pub hyprpaper_config: Option<String>,
+}
+
+/// Default file new entries are added to, per category. Paths are absolute, start
+/// with `~/` or are relative to hyprland.conf; `None` lets hyprlang pick the file.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TargetPreferences {
+    pub keybinds: Option<String>,
+    pub bindu: Option<String>,
+    pub env_vars: Option<String>,
+    pub variables: Option<String>,
+}
+
+impl TargetPreferences {
+    pub fn for_category(&self, category: TargetCategory) -> Option<String> {
+        match category {
+            TargetCategory::Keybinds => self.keybinds.clone(),
+            TargetCategory::Bindu => self.bindu.clone(),
+            TargetCategory::EnvVars => self.env_vars.clone(),
+            TargetCategory::Variables => self.variables.clone(),
+        }
+    }
+}
+
 /// Main preferences configuration structure
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PreferencesConfig {
     pub matugen: MatugenPreferences,
+    pub backup: BackupPreferences,
+    pub validation: ValidationPreferences,
+    pub paths: PathPreferences,
+    pub targets: TargetPreferences,
 }
 
-/// Get the configuration file path using XDG_CONFIG_HOME or fallback to ~/.config
-fn get_config_path() -> Result<PathBuf, String> {
-    // Try XDG_CONFIG_HOME first
-    let config_dir = if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
-        PathBuf::from(xdg_config)
-    } else {
-        // Fallback to ~/.config
-        let home_dir = std::env::var("HOME")
-            .map_err(|_| "Could not determine home directory".to_string())?;
-        PathBuf::from(home_dir).join(".config")
-    };
-
-    let hyprconfig_dir = config_dir.join("hyprconfig");
+/// Get the hyprconfig directory (XDG_CONFIG_HOME/hyprconfig)
+pub fn get_hyprconfig_dir() -> Result<PathBuf, BackendError> {
+    let hyprconfig_dir = xdg_config_home()?.join("hyprconfig");
 
     // Create the hyprconfig directory if it doesn't exist
     if !hyprconfig_dir.exists() {
         fs::create_dir_all(&hyprconfig_dir)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+            .map_err(|e| BackendError::io("create", &hyprconfig_dir, e))?;
     }
 
-    Ok(hyprconfig_dir.join("preferences.toml"))
+    Ok(hyprconfig_dir)
+}
+
+/// Get the configuration file path (XDG_CONFIG_HOME/hyprconfig/preferences.toml)
+fn get_config_path() -> Result<PathBuf, BackendError> {
+    Ok(get_hyprconfig_dir()?.join("preferences.toml"))
 }
 
 /// Ensure the config file exists, creating it with defaults if it doesn't
-fn ensure_config_exists(config_path: &PathBuf) -> Result<(), String> {
+fn ensure_config_exists(config_path: &PathBuf) -> Result<(), BackendError> {
     if !config_path.exists() {
         let default_config = r#"[matugen]
 enable = false
 light_mode = false
 generator_type = "scheme-tonal-spot"
+
+[backup]
+enable = true
+max_backups = 50
+max_age_days = 30
+
+[validation]
+auto_rollback = false
+
+[paths]
+hyprland_config = ""
+hyprpaper_config = ""
+
+[targets]
+keybinds = ""
+bindu = ""
+env_vars = ""
+variables = ""
 "#;
         fs::write(config_path, default_config)
-            .map_err(|e| format!("Failed to create default config: {}", e))?;
+            .map_err(|e| BackendError::io("create", config_path, e))?;
     }
     Ok(())
 }
 
 /// Load the configuration file as a mutable TOML document
-fn load_config_document(config_path: &PathBuf) -> Result<DocumentMut, String> {
-    let contents = fs::read_to_string(config_path)
-        .map_err(|e| format!("Failed to read config file: {}", e))?;
+fn load_config_document(config_path: &PathBuf) -> Result<DocumentMut, BackendError> {
+    let contents =
+        fs::read_to_string(config_path).map_err(|e| BackendError::io("read", config_path, e))?;
 
     contents
         .parse::<DocumentMut>()
-        .map_err(|e| format!("Failed to parse TOML: {}", e))
+        .map_err(|e| BackendError::parse_failed(config_path, e))
 }
 
 /// Tauri command to get current preferences
 #[tauri::command]
-pub fn get_preferences() -> Result<PreferencesConfig, String> {
+pub fn get_preferences() -> Result<PreferencesConfig, BackendError> {
     let config_path = get_config_path()?;
     ensure_config_exists(&config_path)?;
 
@@ -98,19 +176,135 @@ pub fn get_preferences() -> Result<PreferencesConfig, String> {
             light_mode,
             generator_type,
         },
+        backup: read_backup_preferences(&doc),
+        validation: read_validation_preferences(&doc),
+        paths: read_path_preferences(&doc),
+        targets: read_target_preferences(&doc),
     })
 }
 
+/// Extract values from the [backup] table with defaults
+fn read_backup_preferences(doc: &DocumentMut) -> BackupPreferences {
+    let defaults = BackupPreferences::default();
+
+    let enable = doc
+        .get("backup")
+        .and_then(|t| t.get("enable"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(defaults.enable);
+
+    let max_backups = doc
+        .get("backup")
+        .and_then(|t| t.get("max_backups"))
+        .and_then(|v| v.as_integer())
+        .map(|v| v.max(1) as usize)
+        .unwrap_or(defaults.max_backups);
+
+    let max_age_days = doc
+        .get("backup")
+        .and_then(|t| t.get("max_age_days"))
+        .and_then(|v| v.as_integer())
+        .map(|v| v.max(0) as u64)
+        .unwrap_or(defaults.max_age_days);
+
+    BackupPreferences {
+        enable,
+        max_backups,
+        max_age_days,
+    }
+}
+
+/// Get the backup preferences, falling back to defaults when they can't be read
+pub fn get_backup_preferences() -> BackupPreferences {
+    get_config_path()
+        .and_then(|path| load_config_document(&path))
+        .map(|doc| read_backup_preferences(&doc))
+        .unwrap_or_default()
+}
+
+/// Extract values from the [validation] table with defaults
+fn read_validation_preferences(doc: &DocumentMut) -> ValidationPreferences {
+    let defaults = ValidationPreferences::default();
+
+    let auto_rollback = doc
+        .get("validation")
+        .and_then(|t| t.get("auto_rollback"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(defaults.auto_rollback);
+
+    ValidationPreferences { auto_rollback }
+}
+
+/// Get the validation preferences, falling back to defaults when they can't be read
+pub fn get_validation_preferences() -> ValidationPreferences {
+    get_config_path()
+        .and_then(|path| load_config_document(&path))
+        .map(|doc| read_validation_preferences(&doc))
+        .unwrap_or_default()
+}
+
+/// Extract values from the [paths] table, empty strings mean no override
+fn read_path_preferences(doc: &DocumentMut) -> PathPreferences {
+    let read_path = |key: &str| {
+        doc.get("paths")
+            .and_then(|t| t.get(key))
+            .and_then(|v| v.as_str())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    PathPreferences {
+        hyprland_config: read_path("hyprland_config"),
+        hyprpaper_config: read_path("hyprpaper_config"),
+    }
+}
+
+/// Get the config path overrides, falling back to none when they can't be read
+pub fn get_path_preferences() -> PathPreferences {
+    get_config_path()
+        .and_then(|path| load_config_document(&path))
+        .map(|doc| read_path_preferences(&doc))
+        .unwrap_or_default()
+}
+
+/// Extract values from the [targets] table, empty strings mean no default
+fn read_target_preferences(doc: &DocumentMut) -> TargetPreferences {
+    let read_target = |key: &str| {
+        doc.get("targets")
+            .and_then(|t| t.get(key))
+            .and_then(|v| v.as_str())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    TargetPreferences {
+        keybinds: read_target("keybinds"),
+        bindu: read_target("bindu"),
+        env_vars: read_target("env_vars"),
+        variables: read_target("variables"),
+    }
+}
+
+/// Get the default target files, falling back to none when they can't be read
+pub fn get_target_preferences() -> TargetPreferences {
+    get_config_path()
+        .and_then(|path| load_config_document(&path))
+        .map(|doc| read_target_preferences(&doc))
+        .unwrap_or_default()
+}
+
 /// Tauri command to update matugen preferences
 #[tauri::command]
 pub fn update_matugen_preferences(
+    journal: State<'_, Journal>,
     enable: bool,
     light_mode: bool,
     generator_type: String,
-) -> Result<(), String> {
+) -> Result<(), BackendError> {
     let config_path = get_config_path()?;
     ensure_config_exists(&config_path)?;
 
+    let expected_hash = file_hash(&config_path);
     let mut doc = load_config_document(&config_path)?;
 
     // Ensure [matugen] table exists
@@ -124,8 +318,166 @@ pub fn update_matugen_preferences(
     doc["matugen"]["generator_type"] = value(generator_type);
 
     // Write back to file
-    fs::write(&config_path, doc.to_string())
-        .map_err(|e| format!("Failed to write config file: {}", e))?;
+    journal.track(
+        Subsystem::Preferences,
+        "Update matugen preferences",
+        std::slice::from_ref(&config_path),
+        || write_atomic_checked(&config_path, &doc.to_string(), expected_hash),
+    )?;
+
+    Ok(())
+}
+
+/// Tauri command to update backup preferences
+#[tauri::command]
+pub fn update_backup_preferences(
+    journal: State<'_, Journal>,
+    enable: bool,
+    max_backups: usize,
+    max_age_days: u64,
+) -> Result<(), BackendError> {
+    if max_backups == 0 {
+        return Err(BackendError::invalid_input(
+            "At least one backup must be kept",
+        ));
+    }
+
+    let config_path = get_config_path()?;
+    ensure_config_exists(&config_path)?;
+
+    let expected_hash = file_hash(&config_path);
+    let mut doc = load_config_document(&config_path)?;
+
+    // Ensure [backup] table exists
+    if !doc.contains_table("backup") {
+        doc["backup"] = toml_edit::table();
+    }
+
+    doc["backup"]["enable"] = value(enable);
+    doc["backup"]["max_backups"] = value(max_backups as i64);
+    doc["backup"]["max_age_days"] = value(max_age_days as i64);
+
+    journal.track(
+        Subsystem::Preferences,
+        "Update backup preferences",
+        std::slice::from_ref(&config_path),
+        || write_atomic_checked(&config_path, &doc.to_string(), expected_hash),
+    )?;
+
+    Ok(())
+}
+
+/// Tauri command to update validation preferences
+#[tauri::command]
+pub fn update_validation_preferences(
+    journal: State<'_, Journal>,
+    auto_rollback: bool,
+) -> Result<(), BackendError> {
+    let config_path = get_config_path()?;
+    ensure_config_exists(&config_path)?;
+
+    let expected_hash = file_hash(&config_path);
+    let mut doc = load_config_document(&config_path)?;
+
+    // Ensure [validation] table exists
+    if !doc.contains_table("validation") {
+        doc["validation"] = toml_edit::table();
+    }
+
+    doc["validation"]["auto_rollback"] = value(auto_rollback);
+
+    journal.track(
+        Subsystem::Preferences,
+        "Update validation preferences",
+        std::slice::from_ref(&config_path),
+        || write_atomic_checked(&config_path, &doc.to_string(), expected_hash),
+    )?;
+
+    Ok(())
+}
+
+/// Tauri command to update the config path overrides, `None` clears an override
+#[tauri::command]
+pub fn update_path_preferences(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    hyprland_config: Option<String>,
+    hyprpaper_config: Option<String>,
+) -> Result<(), BackendError> {
+    let hyprland_config = hyprland_config.unwrap_or_default().trim().to_string();
+    let hyprpaper_config = hyprpaper_config.unwrap_or_default().trim().to_string();
+
+    for path in [&hyprland_config, &hyprpaper_config] {
+        if !path.is_empty() && !path.starts_with("~/") && !Path::new(path).is_absolute() {
+            return Err(BackendError::invalid_input(format!(
+                "Config path '{}' must be absolute or start with ~/",
+                path
+            )));
+        }
+    }
+
+    let config_path = get_config_path()?;
+    ensure_config_exists(&config_path)?;
+
+    let expected_hash = file_hash(&config_path);
+    let mut doc = load_config_document(&config_path)?;
+
+    // Ensure [paths] table exists
+    if !doc.contains_table("paths") {
+        doc["paths"] = toml_edit::table();
+    }
+
+    doc["paths"]["hyprland_config"] = value(hyprland_config);
+    doc["paths"]["hyprpaper_config"] = value(hyprpaper_config);
+
+    journal.track(
+        Subsystem::Preferences,
+        "Update config paths",
+        std::slice::from_ref(&config_path),
+        || write_atomic_checked(&config_path, &doc.to_string(), expected_hash),
+    )?;
+
+    // The parsed config may belong to the old path
+    session.invalidate();
+
+    Ok(())
+}
+
+/// Tauri command to update the default target files, `None` clears a default
+#[tauri::command]
+pub fn update_target_preferences(
+    journal: State<'_, Journal>,
+    keybinds: Option<String>,
+    bindu: Option<String>,
+    env_vars: Option<String>,
+    variables: Option<String>,
+) -> Result<(), BackendError> {
+    let config_path = get_config_path()?;
+    ensure_config_exists(&config_path)?;
+
+    let expected_hash = file_hash(&config_path);
+    let mut doc = load_config_document(&config_path)?;
+
+    // Ensure [targets] table exists
+    if !doc.contains_table("targets") {
+        doc["targets"] = toml_edit::table();
+    }
+
+    for (key, target) in [
+        ("keybinds", keybinds),
+        ("bindu", bindu),
+        ("env_vars", env_vars),
+        ("variables", variables),
+    ] {
+        doc["targets"][key] = value(target.unwrap_or_default().trim());
+    }
+
+    journal.track(
+        Subsystem::Preferences,
+        "Update default target files",
+        std::slice::from_ref(&config_path),
+        || write_atomic_checked(&config_path, &doc.to_string(), expected_hash),
+    )?;
 
     Ok(())
 }
@@ -347,38 +699,20 @@ const MATUGEN_TEMPLATE: &str = r#"/* Hyprconfig Theme - Matugen Template
 "#;
 
 /// Get the theme CSS file path (XDG_CONFIG_HOME/hyprconfig/theme.css)
-fn get_theme_css_path() -> Result<PathBuf, String> {
-    let config_dir = if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
-        PathBuf::from(xdg_config)
-    } else {
-        let home_dir = std::env::var("HOME")
-            .map_err(|_| "Could not determine home directory".to_string())?;
-        PathBuf::from(home_dir).join(".config")
-    };
-
-    let hyprconfig_dir = config_dir.join("hyprconfig");
-
-    if !hyprconfig_dir.exists() {
-        fs::create_dir_all(&hyprconfig_dir)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
-
-    Ok(hyprconfig_dir.join("theme.css"))
+fn get_theme_css_path() -> Result<PathBuf, BackendError> {
+    Ok(get_hyprconfig_dir()?.join("theme.css"))
 }
 
-/// Get the matugen template file path (~/.config/matugen/templates/hyprconfig.css)
-fn get_matugen_template_path() -> Result<PathBuf, String> {
-    let home_dir = std::env::var("HOME")
-        .map_err(|_| "Could not determine home directory".to_string())?;
-
-    let templates_dir = PathBuf::from(home_dir)
+/// Get the matugen template file path (XDG_CONFIG_HOME/matugen/templates/hyprconfig.css)
+fn get_matugen_template_path() -> Result<PathBuf, BackendError> {
+    let templates_dir = home_dir()?
         .join(".config")
         .join("matugen")
         .join("templates");
 
     if !templates_dir.exists() {
         fs::create_dir_all(&templates_dir)
-            .map_err(|e| format!("Failed to create matugen templates directory: {}", e))?;
+            .map_err(|e| BackendError::io("create", &templates_dir, e))?;
     }
 
     Ok(templates_dir.join("hyprconfig.css"))
@@ -386,26 +720,25 @@ fn get_matugen_template_path() -> Result<PathBuf, String> {
 
 /// Tauri command to get theme CSS content, creating default if missing
 #[tauri::command]
-pub fn get_theme_css() -> Result<String, String> {
+pub fn get_theme_css() -> Result<String, BackendError> {
     let theme_path = get_theme_css_path()?;
 
     if !theme_path.exists() {
         fs::write(&theme_path, DEFAULT_THEME_CSS)
-            .map_err(|e| format!("Failed to create default theme CSS: {}", e))?;
+            .map_err(|e| BackendError::io("create", &theme_path, e))?;
     }
 
-    fs::read_to_string(&theme_path)
-        .map_err(|e| format!("Failed to read theme CSS: {}", e))
+    fs::read_to_string(&theme_path).map_err(|e| BackendError::io("read", &theme_path, e))
 }
 
 /// Tauri command to ensure matugen template exists
 #[tauri::command]
-pub fn ensure_matugen_template() -> Result<String, String> {
+pub fn ensure_matugen_template() -> Result<String, BackendError> {
     let template_path = get_matugen_template_path()?;
 
     if !template_path.exists() {
         fs::write(&template_path, MATUGEN_TEMPLATE)
-            .map_err(|e| format!("Failed to create matugen template: {}", e))?;
+            .map_err(|e| BackendError::io("create", &template_path, e))?;
     }
 
     Ok(template_path.to_string_lossy().to_string())
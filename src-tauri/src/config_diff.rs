@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+
+use crate::backup_backend::FileChange;
+
+/// Lines of unchanged context around each hunk
+const CONTEXT_LINES: usize = 3;
+
+/// Unified diff of a single file affected by a mutation
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileDiff {
+    pub path: String,
+    pub diff: String,
+}
+
+/// Build a unified diff for every changed file
+pub fn diff_changes(changes: &[FileChange]) -> Vec<FileDiff> {
+    changes
+        .iter()
+        .map(|change| {
+            let path = change.path.display().to_string();
+
+            // Files that don't exist on one side are diffed against /dev/null like git does
+            let old_header = match change.before {
+                Some(_) => path.clone(),
+                None => "/dev/null".to_string(),
+            };
+            let new_header = match change.after {
+                Some(_) => path.clone(),
+                None => "/dev/null".to_string(),
+            };
+
+            let before = change.before.as_deref().unwrap_or("");
+            let after = change.after.as_deref().unwrap_or("");
+
+            let diff = TextDiff::from_lines(before, after)
+                .unified_diff()
+                .context_radius(CONTEXT_LINES)
+                .header(&old_header, &new_header)
+                .to_string();
+
+            FileDiff { path, diff }
+        })
+        .collect()
+}
@@ -0,0 +1,342 @@
+use hyprland::data::{Monitor, Monitors};
+use hyprland::shared::HyprData;
+use serde::{Deserialize, Serialize};
+use std::panic;
+use tauri::State;
+
+use crate::backend_error::BackendError;
+use crate::color::parse_color;
+use crate::config_session::{ConfigSession, MutationResult};
+use crate::handler_ids::handler_entries;
+use crate::hyprland_backend::hyprctl_keyword;
+use crate::journal_backend::Journal;
+use crate::options_backend::{
+    OptionInfo, OptionValue, format_option_value, parse_option_value, write_option,
+};
+use crate::options_catalog::{CatalogOption, OptionKind, build_catalog};
+
+/// An option whose runtime value differs from what a reload would apply
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OptionDrift {
+    pub key: String,
+    pub kind: OptionKind,
+    /// What the config sets, `value` is `None` when the default applies
+    pub config: OptionInfo,
+    pub default: Option<serde_json::Value>,
+    pub runtime: OptionValue,
+}
+
+/// A monitor whose live mode, position or scale differs from its `monitor =` line.
+/// Persisting it is done with `save_monitor_settings` and the runtime values.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MonitorDrift {
+    pub name: String,
+    /// The `monitor =` value in the config
+    pub config: String,
+    pub width: u16,
+    pub height: u16,
+    pub refresh_rate: f32,
+    pub x: i32,
+    pub y: i32,
+    pub scale: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigDrift {
+    pub options: Vec<OptionDrift>,
+    pub monitors: Vec<MonitorDrift>,
+}
+
+/// Parts of a value that are compared, numbers with a tolerance
+#[derive(Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+}
+
+/// Parse a color into one ARGB number
+fn parse_argb(token: &str) -> Option<u32> {
+    parse_color(token).map(|(color, _)| color.argb())
+}
+
+fn tokens(value: &OptionValue) -> Vec<Token> {
+    let text = |text: &str| {
+        text.split_whitespace()
+            .map(|token| {
+                if let Some(color) = parse_argb(token) {
+                    Token::Number(color as f64)
+                } else if let Ok(number) = token.trim_end_matches("deg").parse::<f64>() {
+                    Token::Number(number)
+                } else {
+                    Token::Text(token.to_string())
+                }
+            })
+            .collect()
+    };
+
+    match value {
+        OptionValue::Int(int) => vec![Token::Number(*int as f64)],
+        OptionValue::Float(float) => vec![Token::Number(*float)],
+        OptionValue::Bool(bool) => vec![Token::Number(if *bool { 1.0 } else { 0.0 })],
+        OptionValue::Vec2([x, y]) => vec![Token::Number(*x), Token::Number(*y)],
+        OptionValue::Color(color) => text(color),
+        OptionValue::String(string) => text(string),
+        OptionValue::Gradient { .. } => format_option_value(value)
+            .map(|v| text(&v))
+            .unwrap_or_default(),
+    }
+}
+
+fn values_match(a: &OptionValue, b: &OptionValue) -> bool {
+    let (a, b) = (tokens(a), tokens(b));
+
+    a.len() == b.len()
+        && a.iter().zip(&b).all(|pair| match pair {
+            (Token::Number(a), Token::Number(b)) => (a - b).abs() < 1e-3,
+            (a, b) => a == b,
+        })
+}
+
+/// The default from `hyprctl descriptions` as a typed value
+fn default_value(default: &serde_json::Value, kind: OptionKind) -> Option<OptionValue> {
+    match default {
+        serde_json::Value::Bool(bool) => Some(OptionValue::Bool(*bool)),
+        serde_json::Value::Number(number) if kind == OptionKind::Color => number
+            .as_i64()
+            .map(|color| OptionValue::Color(format!("0x{:08x}", color as u32))),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(int) => Some(OptionValue::Int(int)),
+            None => number.as_f64().map(OptionValue::Float),
+        },
+        serde_json::Value::String(string) => Some(parse_option_value(string)),
+        serde_json::Value::Array(pair) => match pair.as_slice() {
+            [x, y] => Some(OptionValue::Vec2([x.as_f64()?, y.as_f64()?])),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Value a config reload would apply: the config's own, else the default
+fn expected_value(option: &CatalogOption) -> Option<OptionValue> {
+    match &option.config.value {
+        Some(value) => Some(value.clone()),
+        None => default_value(option.default.as_ref()?, option.kind),
+    }
+}
+
+fn option_drift(option: CatalogOption) -> Option<OptionDrift> {
+    let runtime = option.runtime.clone()?;
+    let expected = expected_value(&option)?;
+
+    if values_match(&expected, &runtime) {
+        return None;
+    }
+
+    Some(OptionDrift {
+        key: option.key,
+        kind: option.kind,
+        config: option.config,
+        default: option.default,
+        runtime,
+    })
+}
+
+/// Compare a live monitor with the explicit parts of its `monitor =` value;
+/// `preferred`, `auto` and the like match anything
+fn monitor_matches(monitor: &Monitor, config: &str) -> bool {
+    let parts: Vec<&str> = config.split(',').map(str::trim).collect();
+
+    if parts.get(1).is_some_and(|p| *p == "disable") {
+        return true;
+    }
+
+    if let Some(mode) = parts.get(1) {
+        let (resolution, rate) = match mode.split_once('@') {
+            Some((resolution, rate)) => (resolution, Some(rate.trim_end_matches("Hz"))),
+            None => (*mode, None),
+        };
+
+        if let Some((width, height)) = resolution.split_once('x')
+            && let (Ok(width), Ok(height)) = (width.parse::<u16>(), height.parse::<u16>())
+            && (width, height) != (monitor.width, monitor.height)
+        {
+            return false;
+        }
+
+        if let Some(Ok(rate)) = rate.map(str::parse::<f32>)
+            && (rate - monitor.refresh_rate).abs() > 0.5
+        {
+            return false;
+        }
+    }
+
+    if let Some((x, y)) = parts.get(2).and_then(|p| p.split_once('x'))
+        && let (Ok(x), Ok(y)) = (x.parse::<i32>(), y.parse::<i32>())
+        && (x, y) != (monitor.x, monitor.y)
+    {
+        return false;
+    }
+
+    if let Some(Ok(scale)) = parts.get(3).map(|p| p.parse::<f32>())
+        && (scale - monitor.scale).abs() > 0.01
+    {
+        return false;
+    }
+
+    true
+}
+
+/// `monitor =` values from the config, keyed by monitor name
+fn configured_monitors(session: &ConfigSession) -> Result<Vec<(String, String)>, BackendError> {
+    session.read(|hypr, _| {
+        Ok(handler_entries(hypr, "monitor")
+            .into_iter()
+            .filter_map(|entry| {
+                let name = entry.value.split(',').next()?.trim().to_string();
+                Some((name, entry.value))
+            })
+            .collect())
+    })
+}
+
+fn monitor_drift(session: &ConfigSession) -> Result<Vec<MonitorDrift>, BackendError> {
+    let monitors = match panic::catch_unwind(Monitors::get) {
+        Ok(Ok(monitors)) => monitors,
+        _ => return Ok(Vec::new()),
+    };
+    let configured = configured_monitors(session)?;
+
+    Ok(monitors
+        .into_iter()
+        .filter_map(|monitor| {
+            // A line for the monitor itself wins over the catch-all with an empty name
+            let (_, config) = configured
+                .iter()
+                .rev()
+                .find(|(name, _)| *name == monitor.name)
+                .or_else(|| configured.iter().rev().find(|(name, _)| name.is_empty()))?;
+
+            (!monitor_matches(&monitor, config)).then(|| MonitorDrift {
+                name: monitor.name.clone(),
+                config: config.clone(),
+                width: monitor.width,
+                height: monitor.height,
+                refresh_rate: monitor.refresh_rate,
+                x: monitor.x,
+                y: monitor.y,
+                scale: monitor.scale,
+            })
+        })
+        .collect())
+}
+
+/// Tauri command listing everything the running Hyprland does differently from the
+/// config, i.e. what a reload would undo
+#[tauri::command]
+pub fn get_config_drift(session: State<'_, ConfigSession>) -> Result<ConfigDrift, BackendError> {
+    let catalog = build_catalog(&session, None, false)?;
+    if catalog.version.is_none() {
+        return Err(BackendError::hyprland_not_running(
+            "no running instance to compare with",
+        ));
+    }
+
+    Ok(ConfigDrift {
+        options: catalog
+            .options
+            .into_iter()
+            .filter_map(option_drift)
+            .collect(),
+        monitors: monitor_drift(&session)?,
+    })
+}
+
+fn find_drift(session: &ConfigSession, key: &str) -> Result<OptionDrift, BackendError> {
+    let category = key.rsplit_once(':').map(|(c, _)| c.to_string());
+
+    build_catalog(session, category, false)?
+        .options
+        .into_iter()
+        .filter(|option| option.key == key)
+        .find_map(option_drift)
+        .ok_or_else(|| BackendError::not_found(format!("{} has not drifted from the config", key)))
+}
+
+/// Tauri command writing the runtime value of an option to the config
+#[tauri::command]
+pub fn persist_option_drift(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    key: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let drift = find_drift(&session, &key)?;
+    write_option(&session, &journal, key, &drift.runtime, None, dry_run)
+}
+
+/// Tauri command resetting an option in the running Hyprland to what the config says
+#[tauri::command]
+pub fn discard_option_drift(
+    session: State<'_, ConfigSession>,
+    key: String,
+) -> Result<(), BackendError> {
+    let drift = find_drift(&session, &key)?;
+
+    let value = match (&drift.config.raw, &drift.default) {
+        // The raw value keeps `$variables`, which hyprctl can't resolve
+        (Some(_), _) => drift.config.value.as_ref().map(format_option_value),
+        (None, Some(default)) => default_value(default, drift.kind)
+            .as_ref()
+            .map(format_option_value),
+        (None, None) => None,
+    }
+    .transpose()?
+    .ok_or_else(|| BackendError::not_found(format!("No config or default value for {}", key)))?;
+
+    hyprctl_keyword(&key, &value)
+}
+
+/// Tauri command re-applying a monitor's `monitor =` line to the running Hyprland
+#[tauri::command]
+pub fn discard_monitor_drift(
+    session: State<'_, ConfigSession>,
+    name: String,
+) -> Result<(), BackendError> {
+    let drift = monitor_drift(&session)?
+        .into_iter()
+        .find(|drift| drift.name == name)
+        .ok_or_else(|| {
+            BackendError::not_found(format!("Monitor {} has not drifted from the config", name))
+        })?;
+
+    // A catch-all line has no name of its own, so apply it to this monitor
+    let config = match drift.config.split_once(',') {
+        Some(("", rest)) => format!("{},{}", name, rest),
+        _ => drift.config,
+    };
+
+    hyprctl_keyword("monitor", &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::prefix_runtime_colors;
+
+    #[test]
+    fn runtime_gradients_match_and_persist_as_colors() {
+        let config = parse_option_value("rgba(33ccffee) rgba(00ff99ee) 45deg");
+        let runtime = parse_option_value(&prefix_runtime_colors("ee33ccff ee00ff99 45deg"));
+
+        assert!(values_match(&config, &runtime));
+        assert_eq!(
+            format_option_value(&runtime).unwrap(),
+            "0xee33ccff 0xee00ff99 45deg"
+        );
+        assert!(!values_match(
+            &config,
+            &parse_option_value("0xee33ccff 0xee00ff98 45deg")
+        ));
+    }
+}
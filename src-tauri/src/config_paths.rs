@@ -0,0 +1,299 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backend_error::BackendError;
+use crate::config_backend::get_path_preferences;
+
+/// How deep `locate_config` descends below each search root
+const SEARCH_DEPTH: usize = 4;
+
+/// Stop searching once this many configs were found
+const MAX_SEARCH_RESULTS: usize = 20;
+
+/// Where a config path candidate came from, in the order they are tried
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    /// Path override in preferences.toml
+    Preferences,
+    /// HYPRLAND_CONFIG environment variable
+    Environment,
+    /// `--config` argument of the running Hyprland instance
+    RunningInstance,
+    XdgConfigHome,
+    /// ~/.config when XDG_CONFIG_HOME points elsewhere
+    Home,
+    XdgConfigDirs,
+    /// Found by searching the home directory
+    Search,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigCandidate {
+    pub path: String,
+    pub source: ConfigSource,
+    pub exists: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigLocation {
+    /// The config every backend uses, `None` when no candidate exists
+    pub path: Option<String>,
+    pub source: Option<ConfigSource>,
+    pub candidates: Vec<ConfigCandidate>,
+}
+
+pub fn home_dir() -> Result<PathBuf, BackendError> {
+    std::env::var("HOME")
+        .map(PathBuf::from)
+        .map_err(|_| BackendError::from("Could not determine home directory"))
+}
+
+/// $XDG_CONFIG_HOME, falling back to ~/.config when it is unset or empty
+pub fn xdg_config_home() -> Result<PathBuf, BackendError> {
+    match std::env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+        _ => Ok(home_dir()?.join(".config")),
+    }
+}
+
+/// $XDG_DATA_HOME, falling back to ~/.local/share when it is unset or empty
+pub fn xdg_data_home() -> Result<PathBuf, BackendError> {
+    match std::env::var("XDG_DATA_HOME") {
+        Ok(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+        _ => Ok(home_dir()?.join(".local/share")),
+    }
+}
+
+/// $XDG_DATA_DIRS, falling back to /usr/local/share and /usr/share
+pub fn xdg_data_dirs() -> Vec<PathBuf> {
+    match std::env::var("XDG_DATA_DIRS") {
+        Ok(dirs) if !dirs.is_empty() => std::env::split_paths(&dirs).collect(),
+        _ => vec![
+            PathBuf::from("/usr/local/share"),
+            PathBuf::from("/usr/share"),
+        ],
+    }
+}
+
+/// $XDG_CONFIG_DIRS, falling back to /etc/xdg
+pub fn xdg_config_dirs() -> Vec<PathBuf> {
+    match std::env::var("XDG_CONFIG_DIRS") {
+        Ok(dirs) if !dirs.is_empty() => std::env::split_paths(&dirs).collect(),
+        _ => vec![PathBuf::from("/etc/xdg")],
+    }
+}
+
+/// Expand a leading `~` in a user supplied path
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|_| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Config path passed to the running Hyprland instance with `-c`/`--config`
+fn running_instance_config() -> Option<PathBuf> {
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_default();
+
+    // Hyprland writes its pid to the first line of the instance lock file;
+    // older versions keep the instance directory in /tmp
+    let lock = [
+        Path::new(&runtime_dir).join("hypr"),
+        PathBuf::from("/tmp/hypr"),
+    ]
+    .iter()
+    .find_map(|dir| fs::read_to_string(dir.join(&signature).join("hyprland.lock")).ok())?;
+    let pid = lock.lines().next()?.trim().to_string();
+
+    let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let args: Vec<String> = cmdline
+        .split(|byte| *byte == 0)
+        .map(|arg| String::from_utf8_lossy(arg).to_string())
+        .collect();
+
+    let config = args
+        .windows(2)
+        .find(|pair| pair[0] == "-c" || pair[0] == "--config")
+        .map(|pair| PathBuf::from(&pair[1]))?;
+
+    if config.is_absolute() {
+        Some(config)
+    } else {
+        fs::read_link(format!("/proc/{}/cwd", pid))
+            .ok()
+            .map(|cwd| cwd.join(config))
+    }
+}
+
+fn candidate(path: PathBuf, source: ConfigSource) -> ConfigCandidate {
+    ConfigCandidate {
+        exists: path.is_file(),
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+/// Every place hyprland.conf may live, in order of precedence
+fn hyprland_config_candidates() -> Vec<ConfigCandidate> {
+    let mut candidates = Vec::new();
+
+    if let Some(path) = get_path_preferences().hyprland_config {
+        candidates.push(candidate(expand_home(&path), ConfigSource::Preferences));
+    }
+
+    if let Ok(path) = std::env::var("HYPRLAND_CONFIG")
+        && !path.is_empty()
+    {
+        candidates.push(candidate(expand_home(&path), ConfigSource::Environment));
+    }
+
+    if let Some(path) = running_instance_config() {
+        candidates.push(candidate(path, ConfigSource::RunningInstance));
+    }
+
+    if let Ok(dir) = xdg_config_home() {
+        candidates.push(candidate(
+            dir.join("hypr/hyprland.conf"),
+            ConfigSource::XdgConfigHome,
+        ));
+    }
+
+    if let Ok(home) = home_dir() {
+        let path = home.join(".config/hypr/hyprland.conf");
+        if !candidates.iter().any(|c| Path::new(&c.path) == path) {
+            candidates.push(candidate(path, ConfigSource::Home));
+        }
+    }
+
+    for dir in xdg_config_dirs() {
+        candidates.push(candidate(
+            dir.join("hypr/hyprland.conf"),
+            ConfigSource::XdgConfigDirs,
+        ));
+    }
+
+    candidates
+}
+
+/// Resolve hyprland.conf. When no candidate exists the default location under
+/// $XDG_CONFIG_HOME is returned, so callers report it as missing.
+pub fn get_hyprland_config_path() -> Result<PathBuf, BackendError> {
+    let candidates = hyprland_config_candidates();
+
+    match candidates.iter().find(|c| c.exists) {
+        Some(found) => Ok(PathBuf::from(&found.path)),
+        None => Ok(xdg_config_home()?.join("hypr/hyprland.conf")),
+    }
+}
+
+/// Resolve hyprpaper.conf: the override in preferences.toml, otherwise the first of
+/// $XDG_CONFIG_HOME/hypr and the directory of hyprland.conf that has one. A missing
+/// hyprpaper.conf is fine, it is created under $XDG_CONFIG_HOME/hypr on first write.
+pub fn get_hyprpaper_config_path() -> Result<PathBuf, BackendError> {
+    if let Some(path) = get_path_preferences().hyprpaper_config {
+        return Ok(expand_home(&path));
+    }
+
+    let default_path = xdg_config_home()?.join("hypr/hyprpaper.conf");
+    if default_path.exists() {
+        return Ok(default_path);
+    }
+
+    let beside_hyprland = get_hyprland_config_path()?.with_file_name("hyprpaper.conf");
+    if beside_hyprland.exists() {
+        return Ok(beside_hyprland);
+    }
+
+    Ok(default_path)
+}
+
+/// Collect files named hyprland.conf below `dir`, without following symlinked directories
+fn search_dir(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    if depth == 0 || found.len() >= MAX_SEARCH_RESULTS {
+        return;
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+
+        if file_type.is_dir() {
+            search_dir(&path, depth - 1, found);
+        } else if entry.file_name() == "hyprland.conf"
+            && path.is_file()
+            && found.len() < MAX_SEARCH_RESULTS
+        {
+            found.push(path);
+        }
+    }
+}
+
+/// Search common dotfile locations for hyprland.conf
+fn search_hyprland_configs() -> Vec<PathBuf> {
+    let Ok(home) = home_dir() else {
+        return Vec::new();
+    };
+
+    let mut roots = vec![
+        home.join(".config"),
+        home.join("dotfiles"),
+        home.join(".dotfiles"),
+    ];
+    if let Ok(dir) = xdg_config_home()
+        && !roots.contains(&dir)
+    {
+        roots.insert(0, dir);
+    }
+
+    let mut found = Vec::new();
+    for root in roots {
+        search_dir(&root, SEARCH_DEPTH, &mut found);
+    }
+
+    // A symlinked dotfile shows up under both its link and its target
+    let mut unique: Vec<PathBuf> = Vec::new();
+    for path in found {
+        let canonical = fs::canonicalize(&path).unwrap_or(path);
+        if !unique.contains(&canonical) {
+            unique.push(canonical);
+        }
+    }
+    unique
+}
+
+/// Tauri command reporting which hyprland.conf is used and why. When none of the
+/// known locations has one, the home directory is searched for candidates.
+#[tauri::command]
+pub fn locate_config() -> Result<ConfigLocation, BackendError> {
+    let mut candidates = hyprland_config_candidates();
+
+    if !candidates.iter().any(|c| c.exists) {
+        candidates.extend(
+            search_hyprland_configs()
+                .into_iter()
+                .map(|path| candidate(path, ConfigSource::Search)),
+        );
+    }
+
+    // Search results aren't used until the user picks one as the override
+    let resolved = candidates
+        .iter()
+        .find(|c| c.exists && c.source != ConfigSource::Search);
+
+    Ok(ConfigLocation {
+        path: resolved.map(|c| c.path.clone()),
+        source: resolved.map(|c| c.source),
+        candidates,
+    })
+}
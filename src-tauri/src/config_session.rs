@@ -0,0 +1,427 @@
+use hyprlang::Hyprland;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use crate::atomic_write::{file_hash, write_atomic_checked};
+use crate::backend_error::BackendError;
+use crate::backup_backend::FileChange;
+use crate::config_backend::get_validation_preferences;
+use crate::config_diff::{FileDiff, diff_changes};
+use crate::config_paths::get_hyprland_config_path;
+use crate::config_validation::{ConfigError, config_errors, errors_after_save, reload_hyprland};
+use crate::journal_backend::{Journal, Subsystem};
+use crate::source_graph::collect_config_files;
+
+/// Modification stamp of a file taking part in the parsed config
+#[derive(Debug, Clone, PartialEq)]
+struct FileStamp {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    len: Option<u64>,
+    hash: Option<u64>,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Self {
+        let metadata = fs::metadata(path).ok();
+        FileStamp {
+            path: path.to_path_buf(),
+            modified: metadata.as_ref().and_then(|m| m.modified().ok()),
+            len: metadata.as_ref().map(|m| m.len()),
+            // mtime granularity can hide a quick edit, the content hash can't
+            hash: file_hash(path),
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        *self != FileStamp::read(&self.path)
+    }
+}
+
+/// A parsed hyprland.conf together with the stamps of every file it was built from
+struct LoadedConfig {
+    config_path: PathBuf,
+    hypr: Hyprland,
+    stamps: Vec<FileStamp>,
+}
+
+impl LoadedConfig {
+    fn load(config_path: PathBuf) -> Result<Self, BackendError> {
+        if !config_path.exists() {
+            return Err(BackendError::config_not_found(&config_path)
+                .with_hint("Set the Hyprland config path in preferences"));
+        }
+
+        // Stamp before parsing so an edit racing the parse is picked up next time
+        let stamps = stamp_files(&config_path);
+
+        let mut hypr = Hyprland::new();
+        hypr.parse_file(&config_path)
+            .map_err(|e| BackendError::parse_failed(&config_path, e))?;
+
+        Ok(LoadedConfig {
+            config_path,
+            hypr,
+            stamps,
+        })
+    }
+
+    fn is_stale(&self) -> bool {
+        self.stamps.iter().any(|s| s.is_stale())
+    }
+
+    /// The first file that changed on disk since it was parsed
+    fn conflict(&self) -> Option<BackendError> {
+        self.stamps
+            .iter()
+            .find(|s| s.is_stale())
+            .map(|s| BackendError::conflict(&s.path))
+    }
+
+    /// Hash a file had when it was parsed; `None` for files that didn't exist
+    fn loaded_hash(&self, path: &Path) -> Option<u64> {
+        self.stamps
+            .iter()
+            .find(|s| s.path == path)
+            .and_then(|s| s.hash)
+    }
+}
+
+/// A change to the config files
+enum Edit<F> {
+    /// Mutate the parsed config and save it back through hyprlang
+    Config(F),
+    /// Rewrite the text of one file directly, for edits hyprlang can't express such
+    /// as choosing the file a new entry goes to. The cached config is reparsed after.
+    Rewrite { path: PathBuf, rewrite: TextRewrite },
+}
+
+/// Turns the current contents of a file into its new contents
+pub type TextRewrite = Box<dyn FnOnce(String) -> Result<String, BackendError>>;
+
+/// Closure type of edits that never touch the parsed config
+type NoConfigEdit = fn(&mut Hyprland) -> Result<(), BackendError>;
+
+impl<F: FnOnce(&mut Hyprland) -> Result<(), BackendError>> Edit<F> {
+    /// New contents of every file the edit may touch
+    fn render(
+        self,
+        hypr: &mut Hyprland,
+        paths: &[PathBuf],
+    ) -> Result<Vec<(PathBuf, String)>, BackendError> {
+        match self {
+            Edit::Config(f) => {
+                f(hypr)?;
+                render_documents(hypr, paths)
+            }
+            Edit::Rewrite { path, rewrite } => {
+                let contents =
+                    fs::read_to_string(&path).map_err(|e| BackendError::io("read", &path, e))?;
+                Ok(vec![(path.clone(), rewrite(contents)?)])
+            }
+        }
+    }
+
+    /// Whether the in-memory config matches the files once the edit is saved
+    fn keeps_cache(&self) -> bool {
+        matches!(self, Edit::Config(_))
+    }
+}
+
+/// Outcome of a config mutation as returned to the frontend
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MutationResult {
+    pub diffs: Vec<FileDiff>,
+    /// Errors Hyprland reported after reloading that weren't there before the save
+    pub config_errors: Vec<ConfigError>,
+    /// True when the save was undone again because of `config_errors`
+    pub rolled_back: bool,
+}
+
+/// Long-lived Hyprland config shared by all commands through Tauri managed state.
+///
+/// The config is parsed on first use and kept in memory until one of the files it
+/// was built from changes on disk, at which point it is transparently reparsed.
+#[derive(Default)]
+pub struct ConfigSession {
+    loaded: Mutex<Option<LoadedConfig>>,
+}
+
+impl ConfigSession {
+    fn lock(&self) -> MutexGuard<'_, Option<LoadedConfig>> {
+        // A panic inside a previous command leaves the config in an unknown state,
+        // so drop it and start over instead of failing forever
+        self.loaded.lock().unwrap_or_else(|poisoned| {
+            let mut guard = poisoned.into_inner();
+            *guard = None;
+            guard
+        })
+    }
+
+    /// The config path is only resolved when the config is (re)loaded; changing the
+    /// path preferences drops the session through `invalidate`
+    fn ensure_loaded<'a>(
+        guard: &'a mut MutexGuard<'_, Option<LoadedConfig>>,
+    ) -> Result<&'a mut LoadedConfig, BackendError> {
+        let needs_reload = match guard.as_ref() {
+            Some(loaded) => loaded.is_stale(),
+            None => true,
+        };
+
+        if needs_reload {
+            **guard = None;
+            **guard = Some(LoadedConfig::load(get_hyprland_config_path()?)?);
+        }
+
+        Ok(guard.as_mut().expect("config was just loaded"))
+    }
+
+    /// Run a read-only closure against the current config.
+    /// The closure also receives the path of the main hyprland.conf.
+    pub fn read<T>(
+        &self,
+        f: impl FnOnce(&Hyprland, &Path) -> Result<T, BackendError>,
+    ) -> Result<T, BackendError> {
+        let mut guard = self.lock();
+        let loaded = Self::ensure_loaded(&mut guard)?;
+        f(&loaded.hypr, &loaded.config_path)
+    }
+
+    /// Apply an edit to the current config and save every changed file.
+    ///
+    /// Files are replaced atomically. When one of them was edited on disk since it was
+    /// parsed the write is refused with a conflict instead of clobbering that edit.
+    ///
+    /// Every file the save touched is backed up and recorded in the undo journal under
+    /// `label`. If the edit or the save fails the in-memory config no longer matches
+    /// the files on disk, so it is dropped and reparsed on the next access.
+    fn write<F: FnOnce(&mut Hyprland) -> Result<(), BackendError>>(
+        &self,
+        journal: &Journal,
+        subsystem: Subsystem,
+        label: &str,
+        edit: Edit<F>,
+    ) -> Result<Vec<FileChange>, BackendError> {
+        let mut guard = self.lock();
+
+        // The caller acted on what it last read, so an external edit since then must
+        // surface instead of being silently reparsed and overwritten
+        if let Some(loaded) = guard.as_ref()
+            && let Some(conflict) = loaded.conflict()
+        {
+            *guard = None;
+            return Err(conflict);
+        }
+
+        let loaded = Self::ensure_loaded(&mut guard)?;
+
+        // The save may touch any sourced file, so track all of them
+        let paths = collect_config_files(&loaded.config_path);
+
+        let keeps_cache = edit.keeps_cache();
+
+        let result = journal.track(subsystem, label, &paths, || {
+            for (path, contents) in edit.render(&mut loaded.hypr, &paths)? {
+                // Leave files the mutation didn't touch alone
+                if fs::read_to_string(&path).ok().as_deref() == Some(contents.as_str()) {
+                    continue;
+                }
+                write_atomic_checked(&path, &contents, loaded.loaded_hash(&path))?;
+            }
+
+            Ok(())
+        });
+
+        match result {
+            Ok(changes) if !keeps_cache => {
+                *guard = None;
+                Ok(changes)
+            }
+            Ok(changes) => {
+                // Our own write must not count as an external change
+                loaded.stamps = stamp_files(&loaded.config_path);
+                Ok(changes)
+            }
+            Err(e) => {
+                *guard = None;
+                Err(e)
+            }
+        }
+    }
+
+    /// Apply an edit in memory and return what saving it would change,
+    /// without touching any file.
+    fn preview<F: FnOnce(&mut Hyprland) -> Result<(), BackendError>>(
+        &self,
+        edit: Edit<F>,
+    ) -> Result<Vec<FileChange>, BackendError> {
+        let mut guard = self.lock();
+        let loaded = Self::ensure_loaded(&mut guard)?;
+
+        let paths = collect_config_files(&loaded.config_path);
+        let result = edit.render(&mut loaded.hypr, &paths);
+
+        // The cached config now holds the previewed mutation, so it has to be reparsed
+        *guard = None;
+
+        Ok(result?
+            .into_iter()
+            .filter_map(|(path, after)| {
+                let before = fs::read_to_string(&path).ok();
+                (before.as_deref() != Some(after.as_str())).then_some(FileChange {
+                    path,
+                    before,
+                    after: Some(after),
+                })
+            })
+            .collect())
+    }
+
+    /// Apply a mutation, or only preview it when `dry_run` is set.
+    /// Either way the unified diff of every affected file is returned.
+    ///
+    /// After a real save Hyprland is reloaded and any config error the save introduced
+    /// is reported. With auto rollback enabled such a save is reverted right away.
+    pub fn mutate(
+        &self,
+        journal: &Journal,
+        subsystem: Subsystem,
+        label: &str,
+        dry_run: Option<bool>,
+        f: impl FnOnce(&mut Hyprland) -> Result<(), BackendError>,
+    ) -> Result<MutationResult, BackendError> {
+        self.apply(journal, subsystem, label, dry_run, Edit::Config(f))
+    }
+
+    /// Rewrite the text of the config file `path`, which must be part of the config.
+    /// Saved, previewed and validated just like `mutate`.
+    pub fn rewrite_file(
+        &self,
+        journal: &Journal,
+        subsystem: Subsystem,
+        label: &str,
+        dry_run: Option<bool>,
+        path: PathBuf,
+        rewrite: TextRewrite,
+    ) -> Result<MutationResult, BackendError> {
+        let edit: Edit<NoConfigEdit> = Edit::Rewrite { path, rewrite };
+        self.apply(journal, subsystem, label, dry_run, edit)
+    }
+
+    /// Append `line` to the config file `path`, which must be part of the config
+    pub fn append_line(
+        &self,
+        journal: &Journal,
+        subsystem: Subsystem,
+        label: &str,
+        dry_run: Option<bool>,
+        path: PathBuf,
+        line: String,
+    ) -> Result<MutationResult, BackendError> {
+        let rewrite: TextRewrite = Box::new(move |mut contents| {
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            contents.push_str(&line);
+            contents.push('\n');
+            Ok(contents)
+        });
+        self.rewrite_file(journal, subsystem, label, dry_run, path, rewrite)
+    }
+
+    fn apply<F: FnOnce(&mut Hyprland) -> Result<(), BackendError>>(
+        &self,
+        journal: &Journal,
+        subsystem: Subsystem,
+        label: &str,
+        dry_run: Option<bool>,
+        edit: Edit<F>,
+    ) -> Result<MutationResult, BackendError> {
+        if dry_run.unwrap_or(false) {
+            return Ok(MutationResult {
+                diffs: diff_changes(&self.preview(edit)?),
+                config_errors: Vec::new(),
+                rolled_back: false,
+            });
+        }
+
+        // Errors that already exist aren't ours to report
+        let errors_before = config_errors();
+        let changes = self.write(journal, subsystem, label, edit)?;
+
+        let new_errors = match changes.is_empty() {
+            true => Vec::new(),
+            false => errors_after_save(errors_before),
+        };
+
+        let rolled_back = !new_errors.is_empty() && get_validation_preferences().auto_rollback;
+        if rolled_back {
+            let result = journal.roll_back(&changes, label);
+            self.invalidate();
+            result?;
+
+            if let Err(e) = reload_hyprland() {
+                eprintln!("Failed to reload Hyprland after rollback: {}", e);
+            }
+        }
+
+        Ok(MutationResult {
+            diffs: diff_changes(&changes),
+            config_errors: new_errors,
+            rolled_back,
+        })
+    }
+
+    /// Run `f`, which rewrites config files without going through the session, while
+    /// holding the session lock so no command reads or saves the config meanwhile.
+    /// The cached config is dropped afterwards, even when `f` fails halfway.
+    ///
+    /// The session lock is always taken before the journal lock, so `f` may lock the
+    /// journal but callers must not hold it.
+    pub fn replace_files<T>(
+        &self,
+        f: impl FnOnce() -> Result<T, BackendError>,
+    ) -> Result<T, BackendError> {
+        let mut guard = self.lock();
+        let result = f();
+        *guard = None;
+        result
+    }
+
+    /// Drop the cached config so the next access reparses it from disk
+    pub fn invalidate(&self) {
+        *self.lock() = None;
+    }
+}
+
+/// Render every existing config file as it would be saved
+fn render_documents(
+    hypr: &Hyprland,
+    paths: &[PathBuf],
+) -> Result<Vec<(PathBuf, String)>, BackendError> {
+    paths
+        .iter()
+        .filter(|path| path.exists())
+        .map(|path| {
+            hypr.config()
+                .serialize_file(path)
+                .map(|contents| (path.clone(), contents))
+                .map_err(|e| {
+                    BackendError::from(format!("Failed to render {:?}", path))
+                        .with_file(path)
+                        .with_cause(format!("{:?}", e))
+                })
+        })
+        .collect()
+}
+
+/// Stamp hyprland.conf and every file reachable from it through `source =`
+fn stamp_files(config_path: &Path) -> Vec<FileStamp> {
+    collect_config_files(config_path)
+        .iter()
+        .map(|path| FileStamp::read(path))
+        .collect()
+}
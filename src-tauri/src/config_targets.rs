@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::backend_error::BackendError;
+use crate::config_backend::get_target_preferences;
+use crate::config_paths::home_dir;
+use crate::config_session::ConfigSession;
+use crate::source_graph::collect_config_files;
+
+/// Kinds of entries the add commands create, each with its own default target file
+#[derive(Debug, Clone, Copy)]
+pub enum TargetCategory {
+    Keybinds,
+    Bindu,
+    EnvVars,
+    Variables,
+}
+
+/// A file taking part in the config, i.e. hyprland.conf or a sourced file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigFile {
+    pub path: String,
+    /// Path relative to the directory of hyprland.conf when it lies below it
+    pub relative_path: String,
+    pub exists: bool,
+    pub writable: bool,
+}
+
+fn is_writable(path: &Path) -> bool {
+    fs::metadata(path)
+        .map(|metadata| metadata.is_file() && !metadata.permissions().readonly())
+        .unwrap_or(false)
+}
+
+fn config_file(path: &Path, hypr_dir: &Path) -> ConfigFile {
+    ConfigFile {
+        path: path.display().to_string(),
+        relative_path: path
+            .strip_prefix(hypr_dir)
+            .unwrap_or(path)
+            .display()
+            .to_string(),
+        exists: path.exists(),
+        writable: is_writable(path),
+    }
+}
+
+/// Tauri command listing hyprland.conf and every file it sources
+#[tauri::command]
+pub fn list_config_files(
+    session: State<'_, ConfigSession>,
+) -> Result<Vec<ConfigFile>, BackendError> {
+    session.read(|_, config_path| {
+        let hypr_dir = config_path.parent().unwrap_or(Path::new("/"));
+
+        Ok(collect_config_files(config_path)
+            .iter()
+            .map(|path| config_file(path, hypr_dir))
+            .collect())
+    })
+}
+
+/// Resolve a target given as absolute, `~/` or relative to hyprland.conf's directory.
+/// Only existing, writable files that are part of the config are accepted.
+fn resolve_target(config_path: &Path, target: &str) -> Result<PathBuf, BackendError> {
+    let hypr_dir = config_path.parent().unwrap_or(Path::new("/"));
+
+    let path = match target.strip_prefix("~/") {
+        Some(rest) => home_dir()?.join(rest),
+        None => hypr_dir.join(target),
+    };
+
+    let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+    let found = collect_config_files(config_path)
+        .into_iter()
+        .find(|file| *file == path || fs::canonicalize(file).ok().as_ref() == Some(&canonical));
+
+    match found {
+        Some(file) if is_writable(&file) => Ok(file),
+        Some(file) => {
+            Err(BackendError::invalid_input(format!("{:?} is not writable", file)).with_file(&file))
+        }
+        None => Err(BackendError::invalid_input(format!(
+            "{:?} is not sourced by {:?}",
+            path, config_path
+        ))
+        .with_hint("Add a `source =` line for it to the config first")),
+    }
+}
+
+/// The file new entries of `category` go to: the explicit `target`, else the default
+/// from preferences. `None` leaves the choice to hyprlang.
+pub fn target_file(
+    session: &ConfigSession,
+    category: TargetCategory,
+    target: Option<String>,
+) -> Result<Option<PathBuf>, BackendError> {
+    let target = target
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .or_else(|| get_target_preferences().for_category(category));
+
+    target
+        .map(|target| resolve_target_file(session, &target))
+        .transpose()
+}
+
+/// Resolve a target file given by the user, see `resolve_target`
+pub fn resolve_target_file(session: &ConfigSession, target: &str) -> Result<PathBuf, BackendError> {
+    session.read(|_, config_path| resolve_target(config_path, target))
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use crate::backend_error::BackendError;
+
+/// An error Hyprland reported while loading the config
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ConfigError {
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl ConfigError {
+    /// Parse a line like `Config error in file /path/hyprland.conf at line 12: message`
+    fn parse(line: &str) -> Self {
+        let located = line
+            .strip_prefix("Config error in file ")
+            .and_then(|rest| rest.split_once(" at line "))
+            .and_then(|(file, rest)| {
+                let (line, message) = rest.split_once(": ")?;
+                Some(ConfigError {
+                    file: Some(file.to_string()),
+                    line: line.trim().parse().ok(),
+                    message: message.trim().to_string(),
+                })
+            });
+
+        located.unwrap_or_else(|| ConfigError {
+            file: None,
+            line: None,
+            message: line.to_string(),
+        })
+    }
+
+    /// Line numbers shift with every edit, so errors are compared by file and message
+    fn same_error(&self, other: &ConfigError) -> bool {
+        self.file == other.file && self.message == other.message
+    }
+}
+
+fn hyprland_running() -> bool {
+    std::env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok()
+}
+
+/// Current config errors of the running Hyprland instance, `None` when it isn't running
+pub fn config_errors() -> Option<Vec<ConfigError>> {
+    if !hyprland_running() {
+        return None;
+    }
+
+    // hyprland 0.4 has neither a `data` type nor a `ctl` call for configerrors, so ask
+    // hyprctl directly
+    let output = Command::new("hyprctl")
+        .args(["configerrors", "-j"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let entries: Vec<String> = serde_json::from_slice(&output.stdout).ok()?;
+
+    Some(
+        entries
+            .iter()
+            .flat_map(|entry| entry.lines())
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(ConfigError::parse)
+            .collect(),
+    )
+}
+
+/// Tell Hyprland to reload its config
+pub fn reload_hyprland() -> Result<(), BackendError> {
+    hyprland::ctl::reload::call().map_err(BackendError::hyprland_not_running)
+}
+
+/// Reload Hyprland and return the errors that weren't there in `before`
+pub fn reload_and_check(before: &[ConfigError]) -> Result<Vec<ConfigError>, BackendError> {
+    reload_hyprland()?;
+
+    Ok(config_errors()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|error| !before.iter().any(|old| old.same_error(error)))
+        .collect())
+}
+
+/// Reload after a save and return the errors it introduced. Nothing is checked when
+/// Hyprland wasn't running before the save, i.e. `before` is `None`.
+pub fn errors_after_save(before: Option<Vec<ConfigError>>) -> Vec<ConfigError> {
+    match before {
+        Some(before) => reload_and_check(&before).unwrap_or_else(|e| {
+            eprintln!("Skipping config validation: {}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    }
+}
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::backend_error::BackendError;
+use crate::config_session::{ConfigSession, MutationResult};
+use crate::journal_backend::Journal;
+use crate::option_fields::{
+    Field, FieldKind, field, preview_settings, read_settings, write_settings,
+};
+use crate::preview_backend::{PreviewInfo, Previews};
+
+const BLUR_FIELDS: &[Field] = &[
+    field("enabled", FieldKind::Bool, "true"),
+    field("size", FieldKind::Int { min: 1, max: 20 }, "8"),
+    field("passes", FieldKind::Int { min: 1, max: 10 }, "1"),
+    field("ignore_opacity", FieldKind::Bool, "true"),
+    field("new_optimizations", FieldKind::Bool, "true"),
+    field("xray", FieldKind::Bool, "false"),
+    field("noise", FieldKind::Float { min: 0.0, max: 1.0 }, "0.0117"),
+    field(
+        "contrast",
+        FieldKind::Float { min: 0.0, max: 2.0 },
+        "0.8916",
+    ),
+    field(
+        "brightness",
+        FieldKind::Float { min: 0.0, max: 2.0 },
+        "0.8172",
+    ),
+    field(
+        "vibrancy",
+        FieldKind::Float { min: 0.0, max: 1.0 },
+        "0.1696",
+    ),
+    field(
+        "vibrancy_darkness",
+        FieldKind::Float { min: 0.0, max: 1.0 },
+        "0",
+    ),
+    field("special", FieldKind::Bool, "false"),
+    field("popups", FieldKind::Bool, "false"),
+    field(
+        "popups_ignorealpha",
+        FieldKind::Float { min: 0.0, max: 1.0 },
+        "0.2",
+    ),
+];
+
+const SHADOW_FIELDS: &[Field] = &[
+    field("enabled", FieldKind::Bool, "true"),
+    field("range", FieldKind::Int { min: 0, max: 100 }, "4"),
+    field("render_power", FieldKind::Int { min: 1, max: 4 }, "3"),
+    field("sharp", FieldKind::Bool, "false"),
+    field("ignore_window", FieldKind::Bool, "true"),
+    field("color", FieldKind::Color, "0xee1a1a1a"),
+    field("color_inactive", FieldKind::Color, ""),
+    field("offset", FieldKind::Vec2, "0 0"),
+    field("scale", FieldKind::Float { min: 0.0, max: 1.0 }, "1"),
+];
+
+/// `decoration:blur:*`, with the values in effect (config or Hyprland's default)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlurSettings {
+    pub enabled: bool,
+    pub size: i64,
+    pub passes: i64,
+    pub ignore_opacity: bool,
+    pub new_optimizations: bool,
+    pub xray: bool,
+    pub noise: f64,
+    pub contrast: f64,
+    pub brightness: f64,
+    pub vibrancy: f64,
+    pub vibrancy_darkness: f64,
+    pub special: bool,
+    pub popups: bool,
+    pub popups_ignorealpha: f64,
+}
+
+/// `decoration:shadow:*`, with the values in effect (config or Hyprland's default)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShadowSettings {
+    pub enabled: bool,
+    pub range: i64,
+    pub render_power: i64,
+    pub sharp: bool,
+    pub ignore_window: bool,
+    pub color: String,
+    /// `None` uses `color` for inactive windows too; setting `None` leaves the config as is
+    pub color_inactive: Option<String>,
+    pub offset: [f64; 2],
+    pub scale: f64,
+}
+
+#[tauri::command]
+pub fn get_blur_settings(session: State<'_, ConfigSession>) -> Result<BlurSettings, BackendError> {
+    read_settings(&session, "decoration:blur", BLUR_FIELDS)
+}
+
+/// Tauri command writing the blur options that differ from the values in effect
+#[tauri::command]
+pub fn set_blur_settings(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    settings: BlurSettings,
+    dry_run: Option<bool>,
+) -> Result<Vec<MutationResult>, BackendError> {
+    write_settings(
+        &session,
+        &journal,
+        "decoration:blur",
+        BLUR_FIELDS,
+        &settings,
+        dry_run,
+    )
+}
+
+/// Tauri command trying out blur settings, see `start_preview`
+#[tauri::command]
+pub fn preview_blur_settings(
+    app: AppHandle,
+    session: State<'_, ConfigSession>,
+    previews: State<'_, Previews>,
+    settings: BlurSettings,
+    timeout_secs: Option<u64>,
+) -> Result<PreviewInfo, BackendError> {
+    preview_settings(
+        app,
+        &session,
+        &previews,
+        "decoration:blur",
+        BLUR_FIELDS,
+        &settings,
+        timeout_secs,
+    )
+}
+
+#[tauri::command]
+pub fn get_shadow_settings(
+    session: State<'_, ConfigSession>,
+) -> Result<ShadowSettings, BackendError> {
+    read_settings(&session, "decoration:shadow", SHADOW_FIELDS)
+}
+
+/// Tauri command writing the shadow options that differ from the values in effect
+#[tauri::command]
+pub fn set_shadow_settings(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    settings: ShadowSettings,
+    dry_run: Option<bool>,
+) -> Result<Vec<MutationResult>, BackendError> {
+    write_settings(
+        &session,
+        &journal,
+        "decoration:shadow",
+        SHADOW_FIELDS,
+        &settings,
+        dry_run,
+    )
+}
+
+/// Tauri command trying out shadow settings, see `start_preview`
+#[tauri::command]
+pub fn preview_shadow_settings(
+    app: AppHandle,
+    session: State<'_, ConfigSession>,
+    previews: State<'_, Previews>,
+    settings: ShadowSettings,
+    timeout_secs: Option<u64>,
+) -> Result<PreviewInfo, BackendError> {
+    preview_settings(
+        app,
+        &session,
+        &previews,
+        "decoration:shadow",
+        SHADOW_FIELDS,
+        &settings,
+        timeout_secs,
+    )
+}
@@ -0,0 +1,321 @@
+use hyprlang::Hyprland;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backend_error::{BackendError, ErrorCode};
+use crate::options_backend::expand_variables;
+
+/// A handler call (`bind = ...`, `env = ...`) together with where it is defined
+#[derive(Debug, Clone)]
+pub struct HandlerEntry {
+    /// Stable identifier derived from file, line and content
+    pub id: String,
+    /// Position in `all_handler_calls()`, only valid for the config it was read from
+    pub index: usize,
+    pub value: String,
+    /// The value as written in the file, before `$variables` are expanded. Same as
+    /// `value` when the line wasn't found.
+    pub text: String,
+    pub file: Option<PathBuf>,
+    /// 1-based line number inside `file`
+    pub line: Option<usize>,
+}
+
+/// Byte offset where the comment of `line` starts. `##` is an escaped `#`.
+pub fn comment_start(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'#' {
+            if bytes.get(i + 1) != Some(&b'#') {
+                return Some(i);
+            }
+            i += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split a config line into keyword and value, ignoring comments and unescaping `##`
+pub fn parse_handler_line(line: &str) -> Option<(&str, String)> {
+    let line = &line[..comment_start(line).unwrap_or(line.len())];
+    let (key, value) = line.split_once('=')?;
+    Some((key.trim(), value.trim().replace("##", "#")))
+}
+
+/// The `keyword = value` line `parse_handler_line` reads back, escaping `#` as `##`
+pub fn format_handler_line(keyword: &str, value: &str) -> String {
+    format!("{} = {}", keyword, value.replace('#', "##"))
+}
+
+/// Compare handler values regardless of whitespace around separators
+pub fn normalize(value: &str) -> String {
+    value
+        .split(',')
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// A `keyword = ...` line found by `scan_lines`
+struct ScannedLine {
+    /// 1-based line number
+    number: usize,
+    /// Normalized value with `$variables` expanded, as hyprlang reports it
+    expanded: String,
+    text: String,
+}
+
+/// Every `keyword = ...` line in `path`
+fn scan_lines(path: &Path, keyword: &str, variables: &HashMap<String, String>) -> Vec<ScannedLine> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(number, line)| {
+            let (key, text) = parse_handler_line(line)?;
+            (key == keyword).then(|| ScannedLine {
+                number: number + 1,
+                expanded: normalize(&expand_variables(&text, variables)),
+                text,
+            })
+        })
+        .collect()
+}
+
+/// 64-bit FNV-1a. Ids outlive a build, so they can't use std's hasher, which may
+/// change between Rust releases.
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        // Terminate each part so ("ab", "c") and ("a", "bc") differ
+        for byte in part.iter().chain(&[0xff]) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+pub fn make_id(keyword: &str, file: Option<&Path>, line: Option<usize>, value: &str) -> String {
+    let file = file.map(|f| f.to_string_lossy()).unwrap_or_default();
+    let line = line.map(|l| l.to_string()).unwrap_or_default();
+    let hash = fnv1a(&[
+        keyword.as_bytes(),
+        file.as_bytes(),
+        line.as_bytes(),
+        value.as_bytes(),
+    ]);
+    format!("{:016x}", hash)
+}
+
+/// Every call of the handler `keyword`, in parse order, with a stable id each
+pub fn handler_entries(hypr: &Hyprland, keyword: &str) -> Vec<HandlerEntry> {
+    let values = hypr
+        .config()
+        .all_handler_calls()
+        .get(keyword)
+        .cloned()
+        .unwrap_or_default();
+
+    let variables = hypr.config().variables();
+    let mut lines_by_file: HashMap<PathBuf, Vec<ScannedLine>> = HashMap::new();
+    // Identical lines in one file are told apart by their occurrence
+    let mut seen: HashMap<(PathBuf, String), usize> = HashMap::new();
+
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let file = hypr
+                .config()
+                .get_key_source_file(&format!("{}:{}", keyword, index))
+                .map(Path::to_path_buf);
+
+            let found = file.as_ref().and_then(|file| {
+                let normalized = normalize(&value);
+                let occurrence = seen.entry((file.clone(), normalized.clone())).or_insert(0);
+                let lines = lines_by_file
+                    .entry(file.clone())
+                    .or_insert_with(|| scan_lines(file, keyword, variables));
+
+                let found = lines
+                    .iter()
+                    .filter(|line| line.expanded == normalized)
+                    .nth(*occurrence)
+                    .map(|line| (line.number, line.text.clone()));
+                *occurrence += 1;
+                found
+            });
+            let (line, text) = match found {
+                Some((line, text)) => (Some(line), text),
+                None => (None, value.clone()),
+            };
+
+            // Hashing the text keeps the id when only a variable's value changes
+            HandlerEntry {
+                id: make_id(keyword, file.as_deref(), line, &text),
+                index,
+                value,
+                text,
+                file,
+                line,
+            }
+        })
+        .collect()
+}
+
+/// Find the current index of the entry with `id`. Fails when the entry changed, moved
+/// or was removed since the id was handed out, so a stale id never hits another line.
+pub fn resolve_handler_id(hypr: &Hyprland, keyword: &str, id: &str) -> Result<usize, BackendError> {
+    handler_entries(hypr, keyword)
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .map(|entry| entry.index)
+        .ok_or_else(|| {
+            BackendError::new(
+                ErrorCode::Conflict,
+                format!("The {} entry no longer matches the config file", keyword),
+            )
+            .with_hint("Reload and try again")
+        })
+}
+
+/// Add a handler call through hyprlang; a value it rejects is reported as invalid input
+pub fn add_handler_value(
+    hypr: &mut Hyprland,
+    keyword: &str,
+    value: String,
+) -> Result<(), BackendError> {
+    let message = format!("Failed to add {} = {}", keyword, value);
+    hypr.config_mut()
+        .add_handler_call(keyword, value)
+        .map_err(|e| BackendError::invalid_input(message).with_cause(format!("{:?}", e)))
+}
+
+/// Remove the handler call at `index` through hyprlang; a failure points at the file
+/// and line of the entry when they are known
+pub fn remove_handler_at(
+    hypr: &mut Hyprland,
+    keyword: &str,
+    index: usize,
+) -> Result<(), BackendError> {
+    let entry = handler_entries(hypr, keyword).into_iter().nth(index);
+
+    match hypr.config_mut().remove_handler_call(keyword, index) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let mut error = BackendError::invalid_input(format!(
+                "Failed to remove {} at index {}",
+                keyword, index
+            ))
+            .with_cause(format!("{:?}", e));
+            if let Some(file) = entry.as_ref().and_then(|entry| entry.file.as_deref()) {
+                error = error.with_file(file);
+            }
+            if let Some(line) = entry.and_then(|entry| entry.line) {
+                error = error.with_line(line);
+            }
+            Err(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_handler_line_drops_comments() {
+        assert_eq!(
+            parse_handler_line("bind = SUPER, Q, exec, kitty # terminal"),
+            Some(("bind", "SUPER, Q, exec, kitty".to_string()))
+        );
+        assert_eq!(parse_handler_line("# bind = SUPER, Q, killactive"), None);
+        assert_eq!(
+            parse_handler_line("windowrule = bordercolor rgba(33ccffee)# blue"),
+            Some(("windowrule", "bordercolor rgba(33ccffee)".to_string()))
+        );
+        assert_eq!(parse_handler_line("no assignment here"), None);
+    }
+
+    #[test]
+    fn parse_handler_line_unescapes_hashes() {
+        assert_eq!(
+            parse_handler_line("exec-once = notify-send '##1' # first"),
+            Some(("exec-once", "notify-send '#1'".to_string()))
+        );
+        assert_eq!(
+            parse_handler_line("env = COLOR,##ff0000"),
+            Some(("env", "COLOR,#ff0000".to_string()))
+        );
+        // The third `#` starts a comment
+        assert_eq!(
+            parse_handler_line("env = A,x###y"),
+            Some(("env", "A,x#".to_string()))
+        );
+    }
+
+    #[test]
+    fn formatted_lines_parse_back() {
+        for (keyword, value) in [
+            ("exec-once", "notify-send '#1'"),
+            ("env", "COLOR,#ff0000"),
+            ("bind", "SUPER, H, exec, echo ## #"),
+            ("bind", "SUPER, Q, killactive"),
+        ] {
+            let line = format_handler_line(keyword, value);
+            assert_eq!(
+                parse_handler_line(&line),
+                Some((keyword, value.to_string())),
+                "{}",
+                line
+            );
+        }
+        assert_eq!(
+            format_handler_line("env", "COLOR,#ff0000"),
+            "env = COLOR,##ff0000"
+        );
+    }
+
+    #[test]
+    fn comment_start_skips_escapes() {
+        assert_eq!(comment_start("a ## b # c"), Some(7));
+        assert_eq!(comment_start("a ## b"), None);
+        assert_eq!(comment_start("#"), Some(0));
+    }
+
+    #[test]
+    fn normalize_ignores_spacing() {
+        assert_eq!(normalize("SUPER , Q,exec , kitty"), "SUPER,Q,exec,kitty");
+    }
+
+    #[test]
+    fn make_id_is_stable() {
+        let id = make_id(
+            "bind",
+            Some(Path::new("/a.conf")),
+            Some(3),
+            "SUPER, Q, killactive",
+        );
+        assert_eq!(id, "ac98f0fcb51cf609");
+        assert_ne!(
+            id,
+            make_id(
+                "bind",
+                Some(Path::new("/a.conf")),
+                Some(4),
+                "SUPER, Q, killactive"
+            )
+        );
+        assert_ne!(
+            make_id("ab", None, None, "c"),
+            make_id("a", None, None, "bc")
+        );
+    }
+}
@@ -0,0 +1,855 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+
+use crate::animations_backend::animation_names;
+use crate::backend_error::BackendError;
+use crate::config_session::{ConfigSession, MutationResult, TextRewrite};
+use crate::config_targets::resolve_target_file;
+use crate::handler_ids::{
+    HandlerEntry, add_handler_value, comment_start, format_handler_line, handler_entries,
+    normalize, parse_handler_line, remove_handler_at, resolve_handler_id,
+};
+use crate::journal_backend::{Journal, Subsystem};
+
+// ============================================================================
+// Argument schemas
+// ============================================================================
+
+/// How a single handler argument is validated and edited
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgKind {
+    Text,
+    /// A shell command, may contain commas
+    Command,
+    Integer,
+    Float,
+    /// 0/1, true/false, on/off or yes/no
+    Bool,
+    /// One of `options`
+    Choice,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HandlerArg {
+    pub name: String,
+    pub kind: ArgKind,
+    pub required: bool,
+    /// The last argument may take the rest of the line, commas included
+    pub rest: bool,
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HandlerSchema {
+    pub keyword: String,
+    pub description: String,
+    pub args: Vec<HandlerArg>,
+}
+
+fn arg(name: &str, kind: ArgKind) -> HandlerArg {
+    HandlerArg {
+        name: name.to_string(),
+        kind,
+        required: true,
+        rest: false,
+        options: Vec::new(),
+    }
+}
+
+fn optional(mut arg: HandlerArg) -> HandlerArg {
+    arg.required = false;
+    arg
+}
+
+fn rest(mut arg: HandlerArg) -> HandlerArg {
+    arg.rest = true;
+    arg
+}
+
+fn choice(name: &str, options: &[&str]) -> HandlerArg {
+    HandlerArg {
+        options: options.iter().map(|o| o.to_string()).collect(),
+        ..arg(name, ArgKind::Choice)
+    }
+}
+
+fn schema(keyword: &str, description: &str, args: Vec<HandlerArg>) -> HandlerSchema {
+    HandlerSchema {
+        keyword: keyword.to_string(),
+        description: description.to_string(),
+        args,
+    }
+}
+
+const BIND_KEYWORDS: &[&str] = &[
+    "bind", "binde", "bindel", "bindl", "bindm", "bindn", "bindr", "bindu",
+];
+
+/// Schemas of the handler keywords Hyprland knows about
+fn handler_schemas() -> Vec<HandlerSchema> {
+    let command = || rest(arg("command", ArgKind::Command));
+
+    let mut schemas = vec![
+        schema(
+            "exec-once",
+            "Run a command once when Hyprland starts",
+            vec![command()],
+        ),
+        schema(
+            "execr-once",
+            "Run a command once when Hyprland starts, without window rules",
+            vec![command()],
+        ),
+        schema(
+            "exec",
+            "Run a command on every config reload",
+            vec![command()],
+        ),
+        schema(
+            "execr",
+            "Run a command on every config reload, without window rules",
+            vec![command()],
+        ),
+        schema(
+            "exec-shutdown",
+            "Run a command when Hyprland exits",
+            vec![command()],
+        ),
+        schema(
+            "env",
+            "Set an environment variable",
+            vec![
+                arg("name", ArgKind::Text),
+                rest(arg("value", ArgKind::Text)),
+            ],
+        ),
+        schema(
+            "workspace",
+            "Workspace rule",
+            vec![
+                arg("workspace", ArgKind::Text),
+                optional(rest(arg("rules", ArgKind::Text))),
+            ],
+        ),
+        schema(
+            "monitor",
+            "Monitor layout",
+            vec![
+                arg("name", ArgKind::Text),
+                arg("resolution", ArgKind::Text),
+                optional(arg("position", ArgKind::Text)),
+                optional(arg("scale", ArgKind::Text)),
+                optional(rest(arg("extra", ArgKind::Text))),
+            ],
+        ),
+        schema(
+            "bezier",
+            "Bezier curve for animations",
+            vec![
+                arg("name", ArgKind::Text),
+                arg("x0", ArgKind::Float),
+                arg("y0", ArgKind::Float),
+                arg("x1", ArgKind::Float),
+                arg("y1", ArgKind::Float),
+            ],
+        ),
+        schema(
+            "animation",
+            "Animation settings",
+            vec![
+                choice("name", &animation_names()),
+                arg("enabled", ArgKind::Bool),
+                optional(arg("speed", ArgKind::Float)),
+                optional(arg("curve", ArgKind::Text)),
+                optional(arg("style", ArgKind::Text)),
+            ],
+        ),
+        schema(
+            "permission",
+            "Permission for a binary",
+            vec![
+                arg("binary", ArgKind::Text),
+                choice("type", &["screencopy", "plugin", "keyboard"]),
+                choice("mode", &["allow", "ask", "deny"]),
+            ],
+        ),
+        schema(
+            "windowrule",
+            "Window rule",
+            vec![
+                arg("rule", ArgKind::Text),
+                rest(arg("window", ArgKind::Text)),
+            ],
+        ),
+        schema(
+            "windowrulev2",
+            "Window rule",
+            vec![
+                arg("rule", ArgKind::Text),
+                rest(arg("window", ArgKind::Text)),
+            ],
+        ),
+        schema(
+            "layerrule",
+            "Layer rule",
+            vec![
+                arg("rule", ArgKind::Text),
+                rest(arg("namespace", ArgKind::Text)),
+            ],
+        ),
+        schema("plugin", "Load a plugin", vec![arg("path", ArgKind::Text)]),
+    ];
+
+    for keyword in BIND_KEYWORDS {
+        schemas.push(schema(
+            keyword,
+            "Keybind",
+            vec![
+                optional(arg("modifiers", ArgKind::Text)),
+                arg("key", ArgKind::Text),
+                arg("dispatcher", ArgKind::Text),
+                optional(rest(arg("params", ArgKind::Command))),
+            ],
+        ));
+    }
+
+    schemas
+}
+
+/// Schema of `keyword`; keywords without one take a single free-form value
+fn schema_for(keyword: &str) -> HandlerSchema {
+    handler_schemas()
+        .into_iter()
+        .find(|s| s.keyword == keyword)
+        .unwrap_or_else(|| {
+            schema(
+                keyword,
+                "",
+                vec![optional(rest(arg("value", ArgKind::Text)))],
+            )
+        })
+}
+
+/// Split a handler value into the arguments of `schema`
+fn split_args(schema: &HandlerSchema, value: &str) -> Vec<String> {
+    let count = schema.args.len().max(1);
+    let has_rest = schema.args.last().is_some_and(|a| a.rest);
+
+    let mut args: Vec<String> = if has_rest {
+        value
+            .splitn(count, ',')
+            .map(|a| a.trim().to_string())
+            .collect()
+    } else {
+        value.split(',').map(|a| a.trim().to_string()).collect()
+    };
+
+    args.resize(args.len().max(schema.args.len()), String::new());
+    args
+}
+
+fn validate_arg(arg: &HandlerArg, value: &str) -> Result<(), BackendError> {
+    let invalid = |expected: &str| {
+        Err(BackendError::invalid_input(format!(
+            "'{}' must be {}, got '{}'",
+            arg.name, expected, value
+        )))
+    };
+
+    if value.contains('\n') {
+        return invalid("a single line");
+    }
+
+    if value.is_empty() {
+        return if arg.required {
+            Err(BackendError::invalid_input(format!(
+                "'{}' is required",
+                arg.name
+            )))
+        } else {
+            Ok(())
+        };
+    }
+
+    if !arg.rest && value.contains(',') {
+        return invalid("free of commas");
+    }
+
+    match arg.kind {
+        ArgKind::Integer if value.parse::<i64>().is_err() => invalid("an integer"),
+        ArgKind::Float if value.parse::<f64>().is_err() => invalid("a number"),
+        ArgKind::Bool
+            if !matches!(
+                value.to_lowercase().as_str(),
+                "0" | "1" | "true" | "false" | "on" | "off" | "yes" | "no"
+            ) =>
+        {
+            invalid("0 or 1")
+        }
+        ArgKind::Choice if !arg.options.iter().any(|o| o == value) => {
+            invalid(&format!("one of {}", arg.options.join(", ")))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Validate `args` against the schema of `keyword` and join them into a handler value
+pub fn format_args(keyword: &str, args: &[String]) -> Result<String, BackendError> {
+    let schema = schema_for(keyword);
+
+    if args.len() > schema.args.len() {
+        return Err(BackendError::invalid_input(format!(
+            "{} takes at most {} arguments",
+            keyword,
+            schema.args.len()
+        )));
+    }
+
+    let args: Vec<&str> = args.iter().map(|a| a.trim()).collect();
+    for (index, spec) in schema.args.iter().enumerate() {
+        validate_arg(spec, args.get(index).copied().unwrap_or(""))?;
+    }
+
+    // Only trailing arguments can be left out, earlier ones keep their position
+    let used = args
+        .iter()
+        .rposition(|a| !a.is_empty())
+        .map_or(0, |last| last + 1);
+
+    Ok(args[..used].join(", "))
+}
+
+fn subsystem_for(keyword: &str) -> Subsystem {
+    match keyword {
+        "bindu" => Subsystem::Bindu,
+        _ if BIND_KEYWORDS.contains(&keyword) => Subsystem::Keybinds,
+        "env" => Subsystem::EnvVars,
+        "monitor" => Subsystem::Monitors,
+        "animation" | "bezier" => Subsystem::Animations,
+        _ => Subsystem::Handlers,
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// A handler call as exposed to the frontend
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HandlerCall {
+    /// Stable id to pass to edit/move/delete, see `handler_ids`
+    pub id: String,
+    pub keyword: String,
+    pub value: String,
+    /// `value` split according to the keyword's schema
+    pub args: Vec<String>,
+    pub source_file: Option<String>,
+    pub line: Option<usize>,
+}
+
+#[tauri::command]
+pub fn get_handler_schemas() -> Result<Vec<HandlerSchema>, BackendError> {
+    Ok(handler_schemas())
+}
+
+/// Tauri command listing every handler keyword that has a schema or is used in the config
+#[tauri::command]
+pub fn get_handler_keywords(
+    session: State<'_, ConfigSession>,
+) -> Result<Vec<String>, BackendError> {
+    session.read(|hypr, _| {
+        let mut keywords: Vec<String> = handler_schemas().into_iter().map(|s| s.keyword).collect();
+
+        for keyword in hypr.config().all_handler_calls().keys() {
+            if !keywords.contains(keyword) {
+                keywords.push(keyword.clone());
+            }
+        }
+
+        keywords.sort();
+        Ok(keywords)
+    })
+}
+
+#[tauri::command]
+pub fn get_handler_calls(
+    session: State<'_, ConfigSession>,
+    keyword: String,
+) -> Result<Vec<HandlerCall>, BackendError> {
+    let schema = schema_for(&keyword);
+
+    session.read(|hypr, config_path| {
+        let hypr_dir = config_path.parent().unwrap();
+
+        Ok(handler_entries(hypr, &keyword)
+            .into_iter()
+            .map(|entry| HandlerCall {
+                id: entry.id,
+                keyword: keyword.clone(),
+                args: split_args(&schema, &entry.value),
+                value: entry.value,
+                source_file: entry
+                    .file
+                    .as_ref()
+                    .map(|p| p.strip_prefix(hypr_dir).unwrap_or(p).display().to_string()),
+                line: entry.line,
+            })
+            .collect())
+    })
+}
+
+/// Tauri command adding a handler call, appended to `target` when given
+#[tauri::command]
+pub fn add_handler_call(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    keyword: String,
+    args: Vec<String>,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let value = format_args(&keyword, &args)?;
+    insert_handler_call(&session, &journal, &keyword, value, target, dry_run)
+}
+
+/// Add a formatted handler value, see `add_handler_call`
+pub fn insert_handler_call(
+    session: &ConfigSession,
+    journal: &Journal,
+    keyword: &str,
+    value: String,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let label = format!("Add {}", keyword);
+    let subsystem = subsystem_for(keyword);
+
+    if let Some(target) = target.filter(|t| !t.trim().is_empty()) {
+        let path = resolve_target_file(session, target.trim())?;
+        let line = format_handler_line(keyword, &value);
+        return session.append_line(journal, subsystem, &label, dry_run, path, line);
+    }
+
+    session.mutate(journal, subsystem, &label, dry_run, |hypr| {
+        add_handler_value(hypr, keyword, value)?;
+
+        Ok(())
+    })
+}
+
+/// Look up the entry with `id`, failing like `resolve_handler_id` when it is gone
+fn find_entry(
+    session: &ConfigSession,
+    keyword: &str,
+    id: &str,
+) -> Result<HandlerEntry, BackendError> {
+    session.read(|hypr, _| {
+        let index = resolve_handler_id(hypr, keyword, id)?;
+        Ok(handler_entries(hypr, keyword).swap_remove(index))
+    })
+}
+
+/// Make sure `line` of `path` still holds what `matches` accepts, so a rewrite never
+/// hits another line
+pub(crate) fn check_line(
+    lines: &[&str],
+    line: usize,
+    path: &Path,
+    matches: impl Fn(&str) -> bool,
+) -> Result<(), BackendError> {
+    if lines.get(line - 1).is_some_and(|l| matches(l)) {
+        Ok(())
+    } else {
+        Err(BackendError::conflict(path).with_line(line))
+    }
+}
+
+/// Whether `text` is the line `entry` was read from
+fn holds_entry(text: &str, keyword: &str, entry: &HandlerEntry) -> bool {
+    parse_handler_line(text)
+        .is_some_and(|(key, value)| key == keyword && normalize(&value) == normalize(&entry.text))
+}
+
+/// Move the 1-based `line` right before `anchor_line`, or after the last line `fits`
+/// accepts when there is no anchor
+pub(crate) fn move_line(
+    lines: &mut Vec<&str>,
+    line: usize,
+    anchor_line: Option<usize>,
+    fits: impl Fn(&str) -> bool,
+) {
+    let moved = lines.remove(line - 1);
+
+    // Line numbers past the removed line shift up by one
+    let insert_at = match anchor_line {
+        Some(anchor_line) if anchor_line > line => anchor_line - 2,
+        Some(anchor_line) => anchor_line - 1,
+        None => lines
+            .iter()
+            .rposition(|l| fits(l))
+            .map_or(line - 1, |last| last + 1),
+    };
+    lines.insert(insert_at, moved);
+}
+
+pub(crate) fn join_lines(lines: &[&str], trailing_newline: bool) -> String {
+    let mut contents = lines.join("\n");
+    if trailing_newline {
+        contents.push('\n');
+    }
+    contents
+}
+
+/// `old` with its value replaced, keeping indentation and a trailing comment
+fn rewrite_line(old: &str, keyword: &str, value: &str) -> String {
+    let indent = &old[..old.len() - old.trim_start().len()];
+    let comment = comment_start(old)
+        .map(|i| format!(" {}", &old[i..]))
+        .unwrap_or_default();
+    format!(
+        "{}{}{}",
+        indent,
+        format_handler_line(keyword, value),
+        comment
+    )
+}
+
+/// Tauri command replacing the value of a handler call in place
+#[tauri::command]
+pub fn edit_handler_call(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    keyword: String,
+    id: String,
+    args: Vec<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let value = format_args(&keyword, &args)?;
+    replace_handler_call(&session, &journal, keyword, id, value, dry_run)
+}
+
+/// Replace the value of a handler call with a formatted one, see `edit_handler_call`
+pub fn replace_handler_call(
+    session: &ConfigSession,
+    journal: &Journal,
+    keyword: String,
+    id: String,
+    value: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let label = format!("Edit {}", keyword);
+    let subsystem = subsystem_for(&keyword);
+    let entry = find_entry(session, &keyword, &id)?;
+
+    // Without a known line the entry can only be replaced, which moves it to the end
+    let (Some(path), Some(line)) = (entry.file.clone(), entry.line) else {
+        return session.mutate(journal, subsystem, &label, dry_run, |hypr| {
+            let index = resolve_handler_id(hypr, &keyword, &id)?;
+            remove_handler_at(hypr, &keyword, index)?;
+            add_handler_value(hypr, &keyword, value)?;
+
+            Ok(())
+        });
+    };
+
+    let file = path.clone();
+    let rewrite: TextRewrite = Box::new(move |contents| {
+        let mut lines: Vec<&str> = contents.lines().collect();
+        check_line(&lines, line, &file, |l| holds_entry(l, &keyword, &entry))?;
+
+        let new = rewrite_line(lines[line - 1], &keyword, &value);
+        lines[line - 1] = &new;
+
+        Ok(join_lines(&lines, contents.ends_with('\n')))
+    });
+
+    session.rewrite_file(journal, subsystem, &label, dry_run, path, rewrite)
+}
+
+/// Tauri command moving a handler call right before the call `before`, or after the
+/// last call of the same keyword when `before` is `None`. Both must be in one file.
+#[tauri::command]
+pub fn move_handler_call(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    keyword: String,
+    id: String,
+    before: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    reorder_handler_call(&session, &journal, keyword, id, before, dry_run)
+}
+
+/// Move a handler call within its file, see `move_handler_call`
+pub(crate) fn reorder_handler_call(
+    session: &ConfigSession,
+    journal: &Journal,
+    keyword: String,
+    id: String,
+    before: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let label = format!("Move {}", keyword);
+    let subsystem = subsystem_for(&keyword);
+    let entry = find_entry(session, &keyword, &id)?;
+    let anchor = before
+        .map(|before| find_entry(session, &keyword, &before))
+        .transpose()?;
+
+    let (Some(path), Some(line)) = (entry.file.clone(), entry.line) else {
+        return Err(BackendError::not_found(format!(
+            "Could not locate the {} entry in its file",
+            keyword
+        )));
+    };
+
+    let anchor_line = match &anchor {
+        Some(anchor) if anchor.file.as_ref() != Some(&path) => {
+            return Err(BackendError::invalid_input(format!(
+                "Entries can only be moved within one file, {:?}",
+                path
+            )));
+        }
+        Some(anchor) => Some(anchor.line.ok_or_else(|| {
+            BackendError::not_found(format!(
+                "Could not locate the {} entry in its file",
+                keyword
+            ))
+        })?),
+        None => None,
+    };
+
+    let file = path.clone();
+    let rewrite: TextRewrite = Box::new(move |contents| {
+        let mut lines: Vec<&str> = contents.lines().collect();
+        check_line(&lines, line, &file, |l| holds_entry(l, &keyword, &entry))?;
+        if let (Some(anchor), Some(anchor_line)) = (&anchor, anchor_line) {
+            check_line(&lines, anchor_line, &file, |l| {
+                holds_entry(l, &keyword, anchor)
+            })?;
+        }
+
+        move_line(&mut lines, line, anchor_line, |l| {
+            parse_handler_line(l).is_some_and(|(key, _)| key == keyword)
+        });
+
+        Ok(join_lines(&lines, contents.ends_with('\n')))
+    });
+
+    session.rewrite_file(journal, subsystem, &label, dry_run, path, rewrite)
+}
+
+#[tauri::command]
+pub fn delete_handler_call(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    keyword: String,
+    id: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    remove_handler_call(&session, &journal, &keyword, &id, dry_run)
+}
+
+/// Remove the handler call with `id`, see `delete_handler_call`
+pub fn remove_handler_call(
+    session: &ConfigSession,
+    journal: &Journal,
+    keyword: &str,
+    id: &str,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let label = format!("Delete {}", keyword);
+
+    session.mutate(journal, subsystem_for(keyword), &label, dry_run, |hypr| {
+        let index = resolve_handler_id(hypr, keyword, id)?;
+
+        remove_handler_at(hypr, keyword, index)?;
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn every_schema_is_well_formed() {
+        for schema in handler_schemas() {
+            let rest = schema.args.iter().position(|a| a.rest);
+            assert!(
+                rest.is_none_or(|i| i == schema.args.len() - 1),
+                "{}: only the last argument can take the rest",
+                schema.keyword
+            );
+            let first_optional = schema.args.iter().position(|a| !a.required);
+            // Binds start with optional modifiers, elsewhere optional arguments trail
+            if !BIND_KEYWORDS.contains(&schema.keyword.as_str()) {
+                assert!(
+                    first_optional.is_none_or(|i| schema.args[i..].iter().all(|a| !a.required)),
+                    "{}: required argument after an optional one",
+                    schema.keyword
+                );
+            }
+            for arg in &schema.args {
+                assert_eq!(arg.kind == ArgKind::Choice, !arg.options.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn splits_values_by_schema() {
+        let bind = schema_for("bind");
+        assert_eq!(
+            split_args(&bind, "SUPER, Q, exec, notify-send a, b"),
+            ["SUPER", "Q", "exec", "notify-send a, b"]
+        );
+        assert_eq!(
+            split_args(&bind, ", F1, killactive"),
+            ["", "F1", "killactive", ""]
+        );
+
+        let bezier = schema_for("bezier");
+        assert_eq!(
+            split_args(&bezier, "ease, 0.25, 0.1, 0.25, 1"),
+            ["ease", "0.25", "0.1", "0.25", "1"]
+        );
+    }
+
+    #[test]
+    fn unknown_keywords_take_a_free_form_value() {
+        let schema = schema_for("nonexistent");
+        assert_eq!(schema.args.len(), 1);
+        assert_eq!(split_args(&schema, "a, b, c"), ["a, b, c"]);
+        assert_eq!(
+            format_args("nonexistent", &strings(&["a, b"])).unwrap(),
+            "a, b"
+        );
+    }
+
+    #[test]
+    fn formats_valid_arguments() {
+        assert_eq!(
+            format_args(
+                "bind",
+                &strings(&[" SUPER ", "Q", "exec", "kitty, --single"])
+            )
+            .unwrap(),
+            "SUPER, Q, exec, kitty, --single"
+        );
+        // Trailing optional arguments are left out, earlier ones keep their place
+        assert_eq!(
+            format_args("bind", &strings(&["", "F1", "killactive", ""])).unwrap(),
+            ", F1, killactive"
+        );
+        assert_eq!(
+            format_args(
+                "permission",
+                &strings(&["/usr/bin/grim", "screencopy", "allow"])
+            )
+            .unwrap(),
+            "/usr/bin/grim, screencopy, allow"
+        );
+        assert_eq!(
+            format_args("animation", &strings(&["windows", "yes", "4.5"])).unwrap(),
+            "windows, yes, 4.5"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        let rejected = [
+            ("bind", vec!["SUPER", "", "exec"]),
+            ("bind", vec!["SUPER", "Q", "exec", "kitty", "extra"]),
+            ("bind", vec!["SUPER", "Q, W", "exec"]),
+            ("bind", vec!["SUPER", "Q", "exec", "a\nb"]),
+            ("bezier", vec!["ease", "0.25", "x", "0.25", "1"]),
+            ("animation", vec!["windows", "maybe"]),
+            ("animation", vec!["nonexistent", "1"]),
+            (
+                "permission",
+                vec!["/usr/bin/grim", "screencopy", "sometimes"],
+            ),
+            ("exec-once", vec![""]),
+        ];
+        for (keyword, args) in rejected {
+            assert!(
+                format_args(keyword, &strings(&args)).is_err(),
+                "{} {:?}",
+                keyword,
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn moves_lines() {
+        let fits = |l: &str| parse_handler_line(l).is_some_and(|(key, _)| key == "bind");
+
+        let mut lines = vec!["bind = a", "bind = b", "bind = c", "other = x"];
+        move_line(&mut lines, 3, Some(1), fits);
+        assert_eq!(lines, ["bind = c", "bind = a", "bind = b", "other = x"]);
+
+        let mut lines = vec!["bind = a", "bind = b", "bind = c", "other = x"];
+        move_line(&mut lines, 1, Some(3), fits);
+        assert_eq!(lines, ["bind = b", "bind = a", "bind = c", "other = x"]);
+
+        let mut lines = vec!["bind = a", "bind = b", "other = x", "bind = c"];
+        move_line(&mut lines, 1, None, fits);
+        assert_eq!(lines, ["bind = b", "other = x", "bind = c", "bind = a"]);
+    }
+
+    #[test]
+    fn checks_lines_before_rewriting() {
+        let entry = HandlerEntry {
+            id: String::new(),
+            index: 0,
+            value: "SUPER, Q, exec, kitty".to_string(),
+            text: "SUPER, Q, exec, $terminal".to_string(),
+            file: None,
+            line: Some(2),
+        };
+        let lines = ["# binds", "bind = SUPER,Q,exec,$terminal # comment"];
+        let path = Path::new("/hypr.conf");
+
+        assert!(check_line(&lines, 2, path, |l| holds_entry(l, "bind", &entry)).is_ok());
+        assert!(check_line(&lines, 1, path, |l| holds_entry(l, "bind", &entry)).is_err());
+        assert!(check_line(&lines, 3, path, |l| holds_entry(l, "bind", &entry)).is_err());
+        assert!(check_line(&lines, 2, path, |l| holds_entry(l, "binde", &entry)).is_err());
+    }
+
+    #[test]
+    fn rewrites_lines_in_place() {
+        assert_eq!(
+            rewrite_line("  env = A,1 # keep", "env", "A,#2"),
+            "  env = A,##2 # keep"
+        );
+        assert_eq!(
+            rewrite_line(
+                "bind = SUPER, Q, exec, kitty",
+                "bind",
+                "SUPER, T, exec, foot"
+            ),
+            "bind = SUPER, T, exec, foot"
+        );
+
+        let line = rewrite_line("\tbind = a ## b # old", "bind", "SUPER, H, exec, echo #1");
+        assert_eq!(line, "\tbind = SUPER, H, exec, echo ##1 # old");
+        assert_eq!(
+            parse_handler_line(&line),
+            Some(("bind", "SUPER, H, exec, echo #1".to_string()))
+        );
+    }
+
+    #[test]
+    fn joins_lines() {
+        assert_eq!(join_lines(&["a", "b"], true), "a\nb\n");
+        assert_eq!(join_lines(&["a", "b"], false), "a\nb");
+    }
+}
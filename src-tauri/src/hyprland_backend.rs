@@ -1,17 +1,22 @@
 use hyprland::data::Monitors;
 use hyprland::shared::HyprData;
-use hyprlang::Hyprland;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::panic;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::process::Command;
-
-fn get_hyprland_config_path() -> Result<PathBuf, String> {
-    let home_dir =
-        std::env::var("HOME").map_err(|_| "Could not determine home directory".to_string())?;
-    Ok(Path::new(&home_dir).join(".config/hypr/hyprland.conf"))
-}
+use tauri::State;
+
+use crate::backend_error::BackendError;
+use crate::color::is_color_value;
+use crate::config_session::{ConfigSession, MutationResult};
+use crate::config_targets::{TargetCategory, target_file};
+use crate::handler_ids::{
+    HandlerEntry, add_handler_value, format_handler_line, handler_entries, remove_handler_at,
+    resolve_handler_id,
+};
+use crate::handlers_backend::replace_handler_call;
+use crate::journal_backend::{Journal, Subsystem};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DisplayMode {
@@ -38,7 +43,7 @@ pub struct MonitorInfo {
 }
 
 #[tauri::command]
-pub fn get_monitors() -> Result<Vec<MonitorInfo>, String> {
+pub fn get_monitors() -> Result<Vec<MonitorInfo>, BackendError> {
     // Wrap in catch_unwind to prevent panics from crossing FFI boundary
     let result = panic::catch_unwind(|| Monitors::get());
 
@@ -74,8 +79,10 @@ pub fn get_monitors() -> Result<Vec<MonitorInfo>, String> {
                 .collect();
             Ok(monitor_infos)
         }
-        Ok(Err(e)) => Err(format!("Failed to get monitor info: {}. Make sure Hyprland is running.", e)),
-        Err(_) => Err("Failed to get monitor info: Internal panic occurred. Make sure Hyprland is running and accessible.".to_string()),
+        Ok(Err(e)) => Err(BackendError::hyprland_not_running(e)),
+        Err(_) => Err(BackendError::hyprland_not_running(
+            "Internal panic occurred",
+        )),
     }
 }
 
@@ -166,20 +173,21 @@ pub struct WifiNetwork {
 }
 
 #[tauri::command]
-pub fn get_network_info() -> Result<Vec<NetworkInterface>, String> {
+pub fn get_network_info() -> Result<Vec<NetworkInterface>, BackendError> {
     let net_path = Path::new("/sys/class/net");
 
     if !net_path.exists() {
-        return Err("Network information not available on this system".to_string());
+        return Err(BackendError::not_found(
+            "Network information not available on this system",
+        ));
     }
 
-    let entries =
-        fs::read_dir(net_path).map_err(|e| format!("Failed to read network interfaces: {}", e))?;
+    let entries = fs::read_dir(net_path).map_err(|e| BackendError::io("read", net_path, e))?;
 
     let mut interfaces = Vec::new();
 
     for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let entry = entry.map_err(|e| BackendError::io("read", net_path, e))?;
         let interface_name = entry.file_name().to_string_lossy().to_string();
 
         // Skip loopback interface
@@ -357,7 +365,7 @@ fn get_wifi_ssid(interface_name: &str) -> Option<String> {
 }
 
 #[tauri::command]
-pub async fn scan_wifi_networks() -> Result<Vec<WifiNetwork>, String> {
+pub async fn scan_wifi_networks() -> Result<Vec<WifiNetwork>, BackendError> {
     // First, trigger a rescan
     let _ = Command::new("nmcli")
         .args(&["device", "wifi", "rescan"])
@@ -377,17 +385,11 @@ pub async fn scan_wifi_networks() -> Result<Vec<WifiNetwork>, String> {
             "list",
         ])
         .output()
-        .map_err(|e| {
-            format!(
-                "Failed to scan WiFi networks: {}. Make sure NetworkManager is installed.",
-                e
-            )
-        })?;
+        .map_err(|e| BackendError::spawn_failed("nmcli", e))?;
 
     if !output.status.success() {
-        return Err(
-            "Failed to scan WiFi networks. Make sure NetworkManager is running.".to_string(),
-        );
+        return Err(BackendError::command_failed("nmcli", &output.stderr)
+            .with_hint("Make sure NetworkManager is running"));
     }
 
     let output_str = String::from_utf8_lossy(&output.stdout);
@@ -466,7 +468,7 @@ pub struct SystemInfo {
 }
 
 #[tauri::command]
-pub fn get_system_info() -> Result<SystemInfo, String> {
+pub fn get_system_info() -> Result<SystemInfo, BackendError> {
     Ok(SystemInfo {
         os: get_os_info(),
         hostname: get_hostname(),
@@ -687,72 +689,68 @@ fn get_cpu_info() -> String {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Keybind {
+    /// Stable id to pass to edit/delete, see `handler_ids`
+    pub id: String,
     pub modifiers: Vec<String>,
     pub key: String,
     pub dispatcher: String,
     pub params: String,
+    pub source_file: Option<String>,
+    pub line: Option<usize>,
 }
 
-#[tauri::command]
-pub fn get_keybinds() -> Result<Vec<Keybind>, String> {
-    let config_path = get_hyprland_config_path()?;
+/// Parse a bind handler entry of the form "MODS, KEY, dispatcher, params"
+fn parse_keybind(entry: HandlerEntry, hypr_dir: &Path) -> Option<Keybind> {
+    // Example: "SUPER, Q, exec, kitty"
+    let parts: Vec<&str> = entry.value.split(',').map(|s| s.trim()).collect();
 
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
+    if parts.len() < 3 {
+        return None;
     }
 
-    // Parse the config file using hyprlang
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    // Get all bind handler calls
-    let binds = hypr
-        .config()
-        .all_handler_calls()
-        .get("bind")
-        .cloned()
-        .unwrap_or_else(Vec::new);
-
-    println!("binds: {:?}", binds);
-
-    let mut keybinds = Vec::new();
-
-    for bind_str in binds {
-        // Parse bind format: "MODS, KEY, dispatcher, params"
-        // Example: "SUPER, Q, exec, kitty"
-        let parts: Vec<&str> = bind_str.split(',').map(|s| s.trim()).collect();
-
-        if parts.len() >= 3 {
-            let mods_str = parts[0];
-            let key = parts[1].to_string();
-            let dispatcher = parts[2].to_string();
-            let params = if parts.len() > 3 {
-                parts[3..].join(", ")
-            } else {
-                String::new()
-            };
-
-            // Split modifiers by space or underscore
-            let modifiers: Vec<String> = mods_str
-                .split(|c: char| c.is_whitespace() || c == '_')
-                .filter(|s| !s.is_empty())
-                .map(|s| s.to_string())
-                .collect();
+    let mods_str = parts[0];
+    let key = parts[1].to_string();
+    let dispatcher = parts[2].to_string();
+    let params = if parts.len() > 3 {
+        parts[3..].join(", ")
+    } else {
+        String::new()
+    };
 
-            keybinds.push(Keybind {
-                modifiers,
-                key,
-                dispatcher,
-                params,
-            });
-        }
-    }
+    // Split modifiers by space or underscore
+    let modifiers: Vec<String> = mods_str
+        .split(|c: char| c.is_whitespace() || c == '_')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect();
+
+    let source_file = entry
+        .file
+        .as_ref()
+        .map(|p| p.strip_prefix(hypr_dir).unwrap_or(p).display().to_string());
+
+    Some(Keybind {
+        id: entry.id,
+        modifiers,
+        key,
+        dispatcher,
+        params,
+        source_file,
+        line: entry.line,
+    })
+}
 
-    Ok(keybinds)
+#[tauri::command]
+pub fn get_keybinds(session: State<'_, ConfigSession>) -> Result<Vec<Keybind>, BackendError> {
+    session.read(|hypr, config_path| {
+        let hypr_dir = config_path.parent().unwrap();
+
+        // Get all bind handler calls
+        Ok(handler_entries(hypr, "bind")
+            .into_iter()
+            .filter_map(|entry| parse_keybind(entry, hypr_dir))
+            .collect())
+    })
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -760,143 +758,104 @@ pub struct Variable {
     pub name: String,
     pub value: String,
     pub source_file: Option<String>,
+    /// The value is a color or gradient, see `color::is_color_value`
+    pub is_color: bool,
 }
 
 #[tauri::command]
-pub fn get_variables() -> Result<Vec<Variable>, String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    // Parse the config file using hyprlang
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    let hypr_dir = config_path.parent().unwrap();
-    let variables_map = hypr.config().variables();
-    let mut variables = Vec::new();
+pub fn get_variables(session: State<'_, ConfigSession>) -> Result<Vec<Variable>, BackendError> {
+    session.read(|hypr, config_path| {
+        let hypr_dir = config_path.parent().unwrap();
+        let variables_map = hypr.config().variables();
+        let mut variables = Vec::new();
+
+        for (name, value) in variables_map {
+            // Get source file using variable key format "$VARNAME"
+            let source_file = hypr
+                .config()
+                .get_key_source_file(&format!("${}", name))
+                .map(|p| p.strip_prefix(hypr_dir).unwrap_or(p).display().to_string());
 
-    for (name, value) in variables_map {
-        // Get source file using variable key format "$VARNAME"
-        let source_file = hypr
-            .config()
-            .get_key_source_file(&format!("${}", name))
-            .map(|p| {
-                p.strip_prefix(hypr_dir)
-                    .unwrap_or(p)
-                    .display()
-                    .to_string()
+            variables.push(Variable {
+                name: name.clone(),
+                value: value.clone(),
+                source_file,
+                is_color: is_color_value(value, variables_map),
             });
+        }
 
-        variables.push(Variable {
-            name: name.clone(),
-            value: value.clone(),
-            source_file,
-        });
-    }
-
-    // Sort by name for consistent display
-    variables.sort_by(|a, b| a.name.cmp(&b.name));
+        // Sort by name for consistent display
+        variables.sort_by(|a, b| a.name.cmp(&b.name));
 
-    Ok(variables)
+        Ok(variables)
+    })
 }
 
 #[tauri::command]
-pub fn set_variable(name: String, value: String) -> Result<(), String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    // Parse the config file using hyprlang
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    // Set the variable (mutation API)
-    hypr.config_mut().set_variable(name.clone(), value.clone());
-
-    // Save the config file
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
-
-    Ok(())
+pub fn set_variable(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    name: String,
+    value: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let label = format!("Set variable ${}", name);
+    session.mutate(&journal, Subsystem::Variables, &label, dry_run, |hypr| {
+        // Set the variable (mutation API)
+        hypr.config_mut().set_variable(name.clone(), value.clone());
+        Ok(())
+    })
 }
 
 #[tauri::command]
-pub fn add_variable(name: String, value: String) -> Result<(), String> {
+pub fn add_variable(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    name: String,
+    value: String,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
     // Validate variable name (alphanumeric + underscore only)
     if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
-        return Err(
-            "Variable name must contain only letters, numbers, and underscores".to_string(),
-        );
+        return Err(BackendError::invalid_input(
+            "Variable name must contain only letters, numbers, and underscores",
+        ));
     }
 
     if name.is_empty() {
-        return Err("Variable name cannot be empty".to_string());
+        return Err(BackendError::invalid_input("Variable name cannot be empty"));
     }
 
-    let config_path = get_hyprland_config_path()?;
+    let label = format!("Add variable ${}", name);
 
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
+    // An existing variable is updated where it is defined rather than redefined
+    let exists = session.read(|hypr, _| Ok(hypr.config().variables().contains_key(&name)))?;
+    if !exists && let Some(path) = target_file(&session, TargetCategory::Variables, target)? {
+        let line = format_handler_line(&format!("${}", name), &value);
+        return session.append_line(&journal, Subsystem::Variables, &label, dry_run, path, line);
     }
 
-    // Parse the config file using hyprlang
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    // Add the variable (same as set_variable in hyprlang)
-    hypr.config_mut().set_variable(name.clone(), value.clone());
-
-    // Save the config file
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
-
-    Ok(())
+    session.mutate(&journal, Subsystem::Variables, &label, dry_run, |hypr| {
+        // Add the variable (same as set_variable in hyprlang)
+        hypr.config_mut().set_variable(name.clone(), value.clone());
+        Ok(())
+    })
 }
 
 #[tauri::command]
-pub fn delete_variable(name: String) -> Result<(), String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    // Parse the config file using hyprlang
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    // Remove the variable (mutation API)
-    hypr.config_mut().remove_variable(&name);
-
-    // Save the config file
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
-
-    Ok(())
+pub fn delete_variable(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    name: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let label = format!("Delete variable ${}", name);
+    session.mutate(&journal, Subsystem::Variables, &label, dry_run, |hypr| {
+        // Remove the variable (mutation API)
+        hypr.config_mut().remove_variable(&name);
+        Ok(())
+    })
 }
 
 // ============================================================================
@@ -905,209 +864,130 @@ pub fn delete_variable(name: String) -> Result<(), String> {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EnvVar {
+    /// Stable id to pass to edit/delete, see `handler_ids`
+    pub id: String,
     pub name: String,
     pub value: String,
-    pub index: usize,
     pub source_file: Option<String>,
+    pub line: Option<usize>,
 }
 
 #[tauri::command]
-pub fn get_env_vars() -> Result<Vec<EnvVar>, String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    let hypr_dir = config_path.parent().unwrap();
-
-    // Get all "env" handler calls
-    let envs = hypr
-        .config()
-        .all_handler_calls()
-        .get("env")
-        .cloned()
-        .unwrap_or_else(Vec::new);
-
-    let mut env_vars = Vec::new();
-
-    for (index, env_str) in envs.iter().enumerate() {
-        // Parse "NAME,value" format - split on first comma only
-        if let Some(comma_pos) = env_str.find(',') {
-            let name = env_str[..comma_pos].trim().to_string();
-            let value = env_str[comma_pos + 1..].trim().to_string();
-
-            // Get source file for this env entry
-            let source_file = hypr
-                .config()
-                .get_key_source_file(&format!("env:{}", index))
-                .map(|p| {
-                    p.strip_prefix(hypr_dir)
-                        .unwrap_or(p)
-                        .display()
-                        .to_string()
+pub fn get_env_vars(session: State<'_, ConfigSession>) -> Result<Vec<EnvVar>, BackendError> {
+    session.read(|hypr, config_path| {
+        let hypr_dir = config_path.parent().unwrap();
+
+        let mut env_vars = Vec::new();
+
+        // Get all "env" handler calls
+        for entry in handler_entries(hypr, "env") {
+            // Parse "NAME,value" format - split on first comma only
+            if let Some((name, value)) = entry.value.split_once(',') {
+                let source_file = entry
+                    .file
+                    .as_ref()
+                    .map(|p| p.strip_prefix(hypr_dir).unwrap_or(p).display().to_string());
+
+                env_vars.push(EnvVar {
+                    id: entry.id,
+                    name: name.trim().to_string(),
+                    value: value.trim().to_string(),
+                    source_file,
+                    line: entry.line,
                 });
-
-            env_vars.push(EnvVar {
-                name,
-                value,
-                index,
-                source_file,
-            });
+            }
         }
-    }
 
-    // Sort by name for consistent display
-    env_vars.sort_by(|a, b| a.name.cmp(&b.name));
+        // Sort by name for consistent display
+        env_vars.sort_by(|a, b| a.name.cmp(&b.name));
 
-    Ok(env_vars)
+        Ok(env_vars)
+    })
 }
 
 #[tauri::command]
-pub fn add_env_var(name: String, value: String) -> Result<(), String> {
+pub fn add_env_var(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    name: String,
+    value: String,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
     if name.is_empty() {
-        return Err("Environment variable name cannot be empty".to_string());
+        return Err(BackendError::invalid_input(
+            "Environment variable name cannot be empty",
+        ));
     }
 
     // Env var names: letters, numbers, underscores
     if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
-        return Err(
-            "Environment variable name must contain only letters, numbers, and underscores"
-                .to_string(),
-        );
-    }
-
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
+        return Err(BackendError::invalid_input(
+            "Environment variable name must contain only letters, numbers, and underscores",
         ));
     }
 
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
     // Format: NAME,value
     let env_args = format!("{},{}", name, value);
+    let label = format!("Add env var {}", name);
 
-    hypr.config_mut()
-        .add_handler_call("env", env_args)
-        .map_err(|e| format!("Failed to add env var: {:?}", e))?;
+    if let Some(path) = target_file(&session, TargetCategory::EnvVars, target)? {
+        let line = format_handler_line("env", &env_args);
+        return session.append_line(&journal, Subsystem::EnvVars, &label, dry_run, path, line);
+    }
 
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
+    session.mutate(&journal, Subsystem::EnvVars, &label, dry_run, |hypr| {
+        add_handler_value(hypr, "env", env_args)?;
 
-    Ok(())
+        Ok(())
+    })
 }
 
 #[tauri::command]
-pub fn edit_env_var(index: usize, name: String, value: String) -> Result<(), String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    // Remove old env var at index
-    hypr.config_mut()
-        .remove_handler_call("env", index)
-        .map_err(|e| format!("Failed to remove env var at index {}: {:?}", index, e))?;
-
-    // Add new env var
+pub fn edit_env_var(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    name: String,
+    value: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    // Rewrite the line in place so the entry keeps its position
     let env_args = format!("{},{}", name, value);
-    hypr.config_mut()
-        .add_handler_call("env", env_args)
-        .map_err(|e| format!("Failed to add env var: {:?}", e))?;
-
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
-
-    Ok(())
+    replace_handler_call(&session, &journal, "env".to_string(), id, env_args, dry_run)
 }
 
 #[tauri::command]
-pub fn delete_env_var(index: usize) -> Result<(), String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    hypr.config_mut()
-        .remove_handler_call("env", index)
-        .map_err(|e| format!("Failed to remove env var at index {}: {:?}", index, e))?;
-
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
-
-    Ok(())
+pub fn delete_env_var(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    session.mutate(
+        &journal,
+        Subsystem::EnvVars,
+        "Delete env var",
+        dry_run,
+        |hypr| {
+            let index = resolve_handler_id(hypr, "env", &id)?;
+
+            remove_handler_at(hypr, "env", index)?;
+
+            Ok(())
+        },
+    )
 }
 
-#[tauri::command]
-pub fn add_keybind(
-    modifiers: Vec<String>,
-    key: String,
-    dispatcher: String,
-    params: String,
-) -> Result<(), String> {
-    // Validate inputs
-    if key.trim().is_empty() {
-        return Err("Key is required".to_string());
-    }
-
-    if dispatcher.trim().is_empty() {
-        return Err("Dispatcher is required".to_string());
-    }
-
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    // Parse the config file using hyprlang
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    // Format bind args: "MODS, KEY, dispatcher, params"
+/// Bind args as written to the config: "MODS, KEY, dispatcher, params"
+fn format_bind_args(modifiers: &[String], key: &str, dispatcher: &str, params: &str) -> String {
     let mods_str = if modifiers.is_empty() {
         String::new()
     } else {
         modifiers.join(" ")
     };
 
-    let bind_args = if mods_str.is_empty() {
+    if mods_str.is_empty() {
         format!("{}, {}, {}", key.trim(), dispatcher.trim(), params.trim())
     } else {
         format!(
@@ -1117,158 +997,152 @@ pub fn add_keybind(
             dispatcher.trim(),
             params.trim()
         )
-    };
-
-    // Add handler call (mutation API)
-    hypr.config_mut()
-        .add_handler_call("bind", bind_args)
-        .map_err(|e| format!("Failed to add keybind: {:?}", e))?;
-
-    // Save the config file
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
-
-    Ok(())
+    }
 }
 
 #[tauri::command]
-pub fn edit_keybind(
-    index: usize,
+#[allow(clippy::too_many_arguments)]
+pub fn add_keybind(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
     modifiers: Vec<String>,
     key: String,
     dispatcher: String,
     params: String,
-) -> Result<(), String> {
-    println!("edit_keybind called: index={}, key={}, dispatcher={}", index, key, dispatcher);
-
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
     // Validate inputs
     if key.trim().is_empty() {
-        return Err("Key is required".to_string());
+        return Err(BackendError::invalid_input("Key is required"));
     }
 
     if dispatcher.trim().is_empty() {
-        return Err("Dispatcher is required".to_string());
+        return Err(BackendError::invalid_input("Dispatcher is required"));
     }
 
-    let config_path = get_hyprland_config_path()?;
-    println!("Config path: {:?}", config_path);
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
+    let bind_args = format_bind_args(&modifiers, &key, &dispatcher, &params);
+
+    if let Some(path) = target_file(&session, TargetCategory::Keybinds, target)? {
+        let line = format_handler_line("bind", &bind_args);
+        return session.append_line(
+            &journal,
+            Subsystem::Keybinds,
+            "Add keybind",
+            dry_run,
+            path,
+            line,
+        );
     }
 
-    // Parse the config file using hyprlang
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    println!("Removing keybind at index {}", index);
-    // Remove old keybind at index
-    hypr.config_mut()
-        .remove_handler_call("bind", index)
-        .map_err(|e| format!("Failed to remove keybind at index {}: {:?}", index, e))?;
-    println!("Removed successfully");
-
-    // Format new bind args
-    let mods_str = if modifiers.is_empty() {
-        String::new()
-    } else {
-        modifiers.join(" ")
-    };
-
-    let bind_args = if mods_str.is_empty() {
-        format!("{}, {}, {}", key.trim(), dispatcher.trim(), params.trim())
-    } else {
-        format!(
-            "{}, {}, {}, {}",
-            mods_str,
-            key.trim(),
-            dispatcher.trim(),
-            params.trim()
-        )
-    };
-
-    // Add new keybind (mutation API)
-    println!("Adding new keybind: {}", bind_args);
-    hypr.config_mut()
-        .add_handler_call("bind", bind_args)
-        .map_err(|e| format!("Failed to add keybind: {:?}", e))?;
-    println!("Added successfully");
-
-    // Save the config file
-    println!("Saving config files...");
-    let saved_files = hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
-    println!("Saved files: {:?}", saved_files);
-
-    Ok(())
+    session.mutate(
+        &journal,
+        Subsystem::Keybinds,
+        "Add keybind",
+        dry_run,
+        |hypr| {
+            // Add handler call (mutation API)
+            add_handler_value(hypr, "bind", bind_args)?;
+
+            Ok(())
+        },
+    )
 }
 
 #[tauri::command]
-pub fn delete_keybind(index: usize) -> Result<(), String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
+#[allow(clippy::too_many_arguments)]
+pub fn edit_keybind(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    modifiers: Vec<String>,
+    key: String,
+    dispatcher: String,
+    params: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    // Validate inputs
+    if key.trim().is_empty() {
+        return Err(BackendError::invalid_input("Key is required"));
     }
 
-    // Parse the config file using hyprlang
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    // Remove handler call at index (mutation API)
-    hypr.config_mut()
-        .remove_handler_call("bind", index)
-        .map_err(|e| format!("Failed to remove keybind at index {}: {:?}", index, e))?;
-
-    // Save the config file
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
+    if dispatcher.trim().is_empty() {
+        return Err(BackendError::invalid_input("Dispatcher is required"));
+    }
 
-    Ok(())
+    let bind_args = format_bind_args(&modifiers, &key, &dispatcher, &params);
+    replace_handler_call(
+        &session,
+        &journal,
+        "bind".to_string(),
+        id,
+        bind_args,
+        dry_run,
+    )
 }
 
 #[tauri::command]
-pub fn apply_monitor_settings(
-    name: String,
+pub fn delete_keybind(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    session.mutate(
+        &journal,
+        Subsystem::Keybinds,
+        "Delete keybind",
+        dry_run,
+        |hypr| {
+            let index = resolve_handler_id(hypr, "bind", &id)?;
+
+            // Remove handler call at index (mutation API)
+            remove_handler_at(hypr, "bind", index)?;
+
+            Ok(())
+        },
+    )
+}
+
+/// Format a `monitor =` value: NAME,WIDTHxHEIGHT@RATEHz,XxY,SCALE
+pub fn monitor_rule(
+    name: &str,
     width: u16,
     height: u16,
     refresh_rate: f32,
     x: i32,
     y: i32,
     scale: f32,
-) -> Result<(), String> {
-    // Format: monitor=NAME,WIDTHxHEIGHT@RATEHz,XxY,SCALE
-    let monitor_config = format!(
+) -> String {
+    format!(
         "{},{}x{}@{:.2}Hz,{}x{},{}",
         name, width, height, refresh_rate, x, y, scale
-    );
+    )
+}
+
+/// Set `keyword` in the running Hyprland without touching the config
+pub fn hyprctl_keyword(keyword: &str, value: &str) -> Result<(), BackendError> {
+    let program = format!("hyprctl keyword {}", keyword);
 
     let output = Command::new("hyprctl")
-        .args(["keyword", "monitor", &monitor_config])
+        .args(["keyword", keyword, value])
         .output()
-        .map_err(|e| format!("Failed to run hyprctl: {}", e))?;
+        .map_err(|e| BackendError::spawn_failed("hyprctl", e))?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to apply monitor settings: {}", stderr));
+        return Err(BackendError::command_failed(&program, &output.stderr));
+    }
+
+    // A rejected value is reported on stdout with a zero exit code
+    if String::from_utf8_lossy(&output.stdout).trim() != "ok" {
+        return Err(BackendError::command_failed(&program, &output.stdout));
     }
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn save_monitor_settings(
+pub fn apply_monitor_settings(
     name: String,
     width: u16,
     height: u16,
@@ -1276,200 +1150,140 @@ pub fn save_monitor_settings(
     x: i32,
     y: i32,
     scale: f32,
-) -> Result<(), String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
+) -> Result<(), BackendError> {
+    let monitor_config = monitor_rule(&name, width, height, refresh_rate, x, y, scale);
+    hyprctl_keyword("monitor", &monitor_config)
+}
 
-    // Parse the config file using hyprlang
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse display config: {:?}", e))?;
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn save_monitor_settings(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    name: String,
+    width: u16,
+    height: u16,
+    refresh_rate: f32,
+    x: i32,
+    y: i32,
+    scale: f32,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let monitor_args = monitor_rule(&name, width, height, refresh_rate, x, y, scale);
+    write_monitor_rule(&session, &journal, &name, monitor_args, dry_run)
+}
 
-    // Format: NAME,WIDTHxHEIGHT@RATEHz,XxY,SCALE
-    let monitor_args = format!(
-        "{},{}x{}@{:.2}Hz,{}x{},{}",
-        name, width, height, refresh_rate, x, y, scale
-    );
-
-    // Get existing monitor handler calls
-    let monitors = hypr
-        .config()
-        .all_handler_calls()
-        .get("monitor")
-        .cloned()
-        .unwrap_or_else(Vec::new);
-
-    // Find and remove the monitor line matching this name (or empty name for fallback)
-    let mut found_index: Option<usize> = None;
-    for (i, monitor_str) in monitors.iter().enumerate() {
-        let parts: Vec<&str> = monitor_str.split(',').collect();
-        if !parts.is_empty() {
-            let existing_name = parts[0].trim();
-            if existing_name == name || (existing_name.is_empty() && name.is_empty()) {
-                found_index = Some(i);
-                break;
+/// Replace the `monitor =` line of `name` (or add one) with `monitor_args`
+pub fn write_monitor_rule(
+    session: &ConfigSession,
+    journal: &Journal,
+    name: &str,
+    monitor_args: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let label = format!("Save monitor settings for {}", name);
+    session.mutate(journal, Subsystem::Monitors, &label, dry_run, |hypr| {
+        // Get existing monitor handler calls
+        let monitors = hypr
+            .config()
+            .all_handler_calls()
+            .get("monitor")
+            .cloned()
+            .unwrap_or_else(Vec::new);
+
+        // Find and remove the monitor line matching this name (or empty name for fallback)
+        let mut found_index: Option<usize> = None;
+        for (i, monitor_str) in monitors.iter().enumerate() {
+            let parts: Vec<&str> = monitor_str.split(',').collect();
+            if !parts.is_empty() {
+                let existing_name = parts[0].trim();
+                if existing_name == name || (existing_name.is_empty() && name.is_empty()) {
+                    found_index = Some(i);
+                    break;
+                }
             }
         }
-    }
 
-    // Remove existing monitor line if found
-    if let Some(index) = found_index {
-        hypr.config_mut()
-            .remove_handler_call("monitor", index)
-            .map_err(|e| format!("Failed to remove existing monitor config: {:?}", e))?;
-    }
-
-    // Add new monitor configuration
-    hypr.config_mut()
-        .add_handler_call("monitor", monitor_args)
-        .map_err(|e| format!("Failed to add monitor config: {:?}", e))?;
+        // Remove existing monitor line if found
+        if let Some(index) = found_index {
+            remove_handler_at(hypr, "monitor", index)?;
+        }
 
-    // Save the config file
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
+        // Add new monitor configuration
+        add_handler_value(hypr, "monitor", monitor_args)?;
 
-    Ok(())
+        Ok(())
+    })
 }
 
 // ==================== BINDU HANDLERS (Universal Submap Bindings) ====================
 
 #[tauri::command]
-pub fn get_all_bindu() -> Result<Vec<Keybind>, String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    let bindus = hypr.all_bindu();
-    let mut keybinds = Vec::new();
-
-    for bind_str in bindus {
-        let parts: Vec<&str> = bind_str.split(',').map(|s| s.trim()).collect();
-
-        if parts.len() >= 3 {
-            let mods_str = parts[0];
-            let key = parts[1].to_string();
-            let dispatcher = parts[2].to_string();
-            let params = if parts.len() > 3 {
-                parts[3..].join(", ")
-            } else {
-                String::new()
-            };
-
-            let modifiers: Vec<String> = mods_str
-                .split(|c: char| c.is_whitespace() || c == '_')
-                .filter(|s| !s.is_empty())
-                .map(|s| s.to_string())
-                .collect();
-
-            keybinds.push(Keybind {
-                modifiers,
-                key,
-                dispatcher,
-                params,
-            });
-        }
-    }
-
-    Ok(keybinds)
+pub fn get_all_bindu(session: State<'_, ConfigSession>) -> Result<Vec<Keybind>, BackendError> {
+    session.read(|hypr, config_path| {
+        let hypr_dir = config_path.parent().unwrap();
+
+        Ok(handler_entries(hypr, "bindu")
+            .into_iter()
+            .filter_map(|entry| parse_keybind(entry, hypr_dir))
+            .collect())
+    })
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn add_bindu(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
     modifiers: Vec<String>,
     key: String,
     dispatcher: String,
     params: String,
-) -> Result<(), String> {
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
     if key.trim().is_empty() {
-        return Err("Key is required".to_string());
+        return Err(BackendError::invalid_input("Key is required"));
     }
 
     if dispatcher.trim().is_empty() {
-        return Err("Dispatcher is required".to_string());
+        return Err(BackendError::invalid_input("Dispatcher is required"));
     }
 
-    let config_path = get_hyprland_config_path()?;
+    let bind_args = format_bind_args(&modifiers, &key, &dispatcher, &params);
 
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
+    if let Some(path) = target_file(&session, TargetCategory::Bindu, target)? {
+        let line = format_handler_line("bindu", &bind_args);
+        return session.append_line(&journal, Subsystem::Bindu, "Add bindu", dry_run, path, line);
     }
 
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    let mods_str = if modifiers.is_empty() {
-        String::new()
-    } else {
-        modifiers.join(" ")
-    };
-
-    let bind_args = if mods_str.is_empty() {
-        format!("{}, {}, {}", key.trim(), dispatcher.trim(), params.trim())
-    } else {
-        format!(
-            "{}, {}, {}, {}",
-            mods_str,
-            key.trim(),
-            dispatcher.trim(),
-            params.trim()
-        )
-    };
-
-    hypr.config_mut()
-        .add_handler_call("bindu", bind_args)
-        .map_err(|e| format!("Failed to add bindu: {:?}", e))?;
+    session.mutate(&journal, Subsystem::Bindu, "Add bindu", dry_run, |hypr| {
+        add_handler_value(hypr, "bindu", bind_args)?;
 
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
-
-    Ok(())
+        Ok(())
+    })
 }
 
 #[tauri::command]
-pub fn delete_bindu(index: usize) -> Result<(), String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    hypr.config_mut()
-        .remove_handler_call("bindu", index)
-        .map_err(|e| format!("Failed to remove bindu at index {}: {:?}", index, e))?;
-
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
-
-    Ok(())
+pub fn delete_bindu(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    session.mutate(
+        &journal,
+        Subsystem::Bindu,
+        "Delete bindu",
+        dry_run,
+        |hypr| {
+            let index = resolve_handler_id(hypr, "bindu", &id)?;
+
+            remove_handler_at(hypr, "bindu", index)?;
+
+            Ok(())
+        },
+    )
 }
 
 // ==================== WINDOWRULE V3 HANDLERS ====================
@@ -1517,102 +1331,78 @@ const WINDOWRULE_EFFECT_PROPERTIES: &[&str] = &[
 ];
 
 #[tauri::command]
-pub fn get_windowrule_names() -> Result<Vec<String>, String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    Ok(hypr.windowrule_names())
+pub fn get_windowrule_names(
+    session: State<'_, ConfigSession>,
+) -> Result<Vec<String>, BackendError> {
+    session.read(|hypr, _| Ok(hypr.windowrule_names()))
 }
 
 #[tauri::command]
-pub fn get_windowrule(name: String) -> Result<Windowrule, String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
+pub fn get_windowrule(
+    session: State<'_, ConfigSession>,
+    name: String,
+) -> Result<Windowrule, BackendError> {
+    session.read(|hypr, _| {
+        let rule = hypr.get_windowrule(&name).map_err(|e| {
+            BackendError::not_found(format!("Windowrule '{}' not found", name))
+                .with_cause(format!("{:?}", e))
+        })?;
 
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    let rule = hypr
-        .get_windowrule(&name)
-        .map_err(|e| format!("Windowrule '{}' not found: {:?}", name, e))?;
-
-    let mut match_properties = Vec::new();
-    let mut effect_properties = Vec::new();
-
-    // Collect match properties
-    for prop in WINDOWRULE_MATCH_PROPERTIES {
-        if let Ok(value) = rule.get_string(&format!("match:{}", prop)) {
-            if !value.is_empty() {
-                match_properties.push(WindowruleProperty {
-                    key: prop.to_string(),
-                    value,
-                    property_type: "match".to_string(),
-                });
+        let mut match_properties = Vec::new();
+        let mut effect_properties = Vec::new();
+
+        // Collect match properties
+        for prop in WINDOWRULE_MATCH_PROPERTIES {
+            if let Ok(value) = rule.get_string(&format!("match:{}", prop)) {
+                if !value.is_empty() {
+                    match_properties.push(WindowruleProperty {
+                        key: prop.to_string(),
+                        value,
+                        property_type: "match".to_string(),
+                    });
+                }
             }
         }
-    }
 
-    // Collect effect properties
-    for prop in WINDOWRULE_EFFECT_PROPERTIES {
-        if let Ok(value) = rule.get_string(prop) {
-            if !value.is_empty() {
-                effect_properties.push(WindowruleProperty {
-                    key: prop.to_string(),
-                    value,
-                    property_type: "effect".to_string(),
-                });
+        // Collect effect properties
+        for prop in WINDOWRULE_EFFECT_PROPERTIES {
+            if let Ok(value) = rule.get_string(prop) {
+                if !value.is_empty() {
+                    effect_properties.push(WindowruleProperty {
+                        key: prop.to_string(),
+                        value,
+                        property_type: "effect".to_string(),
+                    });
+                }
             }
         }
-    }
 
-    Ok(Windowrule {
-        name,
-        match_properties,
-        effect_properties,
+        Ok(Windowrule {
+            name: name.clone(),
+            match_properties,
+            effect_properties,
+        })
     })
 }
 
 #[tauri::command]
-pub fn delete_windowrule(name: String) -> Result<(), String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    hypr.config_mut()
-        .remove_special_category_instance("windowrule", &name)
-        .map_err(|e| format!("Failed to delete windowrule '{}': {:?}", name, e))?;
-
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
+pub fn delete_windowrule(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    name: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let label = format!("Delete windowrule {}", name);
+    session.mutate(&journal, Subsystem::Windowrules, &label, dry_run, |hypr| {
+        hypr.config_mut()
+            .remove_special_category_instance("windowrule", &name)
+            .map_err(|e| {
+                BackendError::not_found(format!("Failed to delete windowrule '{}'", name))
+                    .with_cause(format!("{:?}", e))
+            })?;
 
-    Ok(())
+        Ok(())
+    })
 }
 
 // ==================== LAYERRULE V2 HANDLERS ====================
@@ -1642,98 +1432,111 @@ const LAYERRULE_EFFECT_PROPERTIES: &[&str] = &[
 ];
 
 #[tauri::command]
-pub fn get_layerrule_names() -> Result<Vec<String>, String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    Ok(hypr.layerrule_names())
+pub fn get_layerrule_names(session: State<'_, ConfigSession>) -> Result<Vec<String>, BackendError> {
+    session.read(|hypr, _| Ok(hypr.layerrule_names()))
 }
 
 #[tauri::command]
-pub fn get_layerrule(name: String) -> Result<Layerrule, String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
-
-    let rule = hypr
-        .get_layerrule(&name)
-        .map_err(|e| format!("Layerrule '{}' not found: {:?}", name, e))?;
-
-    let mut match_properties = Vec::new();
-    let mut effect_properties = Vec::new();
+pub fn get_layerrule(
+    session: State<'_, ConfigSession>,
+    name: String,
+) -> Result<Layerrule, BackendError> {
+    session.read(|hypr, _| {
+        let rule = hypr.get_layerrule(&name).map_err(|e| {
+            BackendError::not_found(format!("Layerrule '{}' not found", name))
+                .with_cause(format!("{:?}", e))
+        })?;
 
-    for prop in LAYERRULE_MATCH_PROPERTIES {
-        if let Ok(value) = rule.get_string(&format!("match:{}", prop)) {
-            if !value.is_empty() {
-                match_properties.push(LayerruleProperty {
-                    key: prop.to_string(),
-                    value,
-                    property_type: "match".to_string(),
-                });
+        let mut match_properties = Vec::new();
+        let mut effect_properties = Vec::new();
+
+        for prop in LAYERRULE_MATCH_PROPERTIES {
+            if let Ok(value) = rule.get_string(&format!("match:{}", prop)) {
+                if !value.is_empty() {
+                    match_properties.push(LayerruleProperty {
+                        key: prop.to_string(),
+                        value,
+                        property_type: "match".to_string(),
+                    });
+                }
             }
         }
-    }
 
-    for prop in LAYERRULE_EFFECT_PROPERTIES {
-        if let Ok(value) = rule.get_string(prop) {
-            if !value.is_empty() {
-                effect_properties.push(LayerruleProperty {
-                    key: prop.to_string(),
-                    value,
-                    property_type: "effect".to_string(),
-                });
+        for prop in LAYERRULE_EFFECT_PROPERTIES {
+            if let Ok(value) = rule.get_string(prop) {
+                if !value.is_empty() {
+                    effect_properties.push(LayerruleProperty {
+                        key: prop.to_string(),
+                        value,
+                        property_type: "effect".to_string(),
+                    });
+                }
             }
         }
-    }
 
-    Ok(Layerrule {
-        name,
-        match_properties,
-        effect_properties,
+        Ok(Layerrule {
+            name: name.clone(),
+            match_properties,
+            effect_properties,
+        })
     })
 }
 
 #[tauri::command]
-pub fn delete_layerrule(name: String) -> Result<(), String> {
-    let config_path = get_hyprland_config_path()?;
-
-    if !config_path.exists() {
-        return Err(format!(
-            "Hyprland config file not found at {:?}",
-            config_path
-        ));
-    }
-
-    let mut hypr = Hyprland::new();
-    hypr.parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse Hyprland config: {:?}", e))?;
+pub fn delete_layerrule(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    name: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let label = format!("Delete layerrule {}", name);
+    session.mutate(&journal, Subsystem::Layerrules, &label, dry_run, |hypr| {
+        hypr.config_mut()
+            .remove_special_category_instance("layerrule", &name)
+            .map_err(|e| {
+                BackendError::not_found(format!("Failed to delete layerrule '{}'", name))
+                    .with_cause(format!("{:?}", e))
+            })?;
 
-    hypr.config_mut()
-        .remove_special_category_instance("layerrule", &name)
-        .map_err(|e| format!("Failed to delete layerrule '{}': {:?}", name, e))?;
+        Ok(())
+    })
+}
 
-    hypr.config_mut()
-        .save_all()
-        .map_err(|e| format!("Failed to save config files: {:?}", e))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler_ids::parse_handler_line;
+
+    #[test]
+    fn formats_bind_args() {
+        let modifiers = vec!["SUPER".to_string(), "SHIFT".to_string()];
+        assert_eq!(
+            format_bind_args(&modifiers, " Q ", "exec", " kitty "),
+            "SUPER SHIFT, Q, exec, kitty"
+        );
+        assert_eq!(
+            format_bind_args(&[], "F1", "killactive", ""),
+            "F1, killactive, "
+        );
+    }
 
-    Ok(())
+    #[test]
+    fn appended_lines_keep_hashes() {
+        let bind_args = format_bind_args(&[], "F2", "exec", "notify-send '#1' # x");
+        let lines = [
+            ("$accent", "rgb(ff0000) # red".to_string()),
+            ("env", "COLOR,#ff0000".to_string()),
+            ("bind", bind_args.clone()),
+            ("bindu", bind_args),
+        ];
+        for (keyword, value) in lines {
+            let line = format_handler_line(keyword, &value);
+            assert_eq!(
+                parse_handler_line(&line),
+                Some((keyword, value)),
+                "{}",
+                line
+            );
+        }
+    }
 }
@@ -3,6 +3,14 @@ use hyprlang::{Config, SpecialCategoryDescriptor};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
+use tauri::State;
+
+use crate::atomic_write::{content_hash, write_atomic_checked};
+use crate::backend_error::BackendError;
+use crate::backup_backend::FileChange;
+use crate::config_diff::{FileDiff, diff_changes};
+use crate::config_paths::get_hyprpaper_config_path;
+use crate::journal_backend::{Journal, Subsystem};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Wallpaper {
@@ -28,12 +36,8 @@ fn register_hyprpaper_config(config: &mut Config) {
 }
 
 #[tauri::command]
-pub fn get_hyprpaper_config() -> Result<HyprpaperConfig, String> {
-    // Get Hyprpaper config path
-    let home_dir =
-        std::env::var("HOME").map_err(|_| "Could not determine home directory".to_string())?;
-
-    let config_path = Path::new(&home_dir).join(".config/hypr/hyprpaper.conf");
+pub fn get_hyprpaper_config() -> Result<HyprpaperConfig, BackendError> {
+    let config_path = get_hyprpaper_config_path()?;
 
     if !config_path.exists() {
         // Return empty config if file doesn't exist (config is optional)
@@ -47,7 +51,7 @@ pub fn get_hyprpaper_config() -> Result<HyprpaperConfig, String> {
     register_hyprpaper_config(&mut config);
     config
         .parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse hyprpaper config: {:?}", e))?;
+        .map_err(|e| BackendError::parse_failed(&config_path, e))?;
 
     // Get wallpaper anonymous category instances
     let wallpaper_keys = config.list_special_category_keys("wallpaper");
@@ -84,17 +88,9 @@ pub fn get_hyprpaper_config() -> Result<HyprpaperConfig, String> {
     Ok(HyprpaperConfig { wallpapers })
 }
 
-#[tauri::command]
-pub fn set_wallpaper(monitor: String, path: String, fit_mode: Option<String>) -> Result<(), String> {
-    // Validate path
-    if path.trim().is_empty() {
-        return Err("Path cannot be empty".to_string());
-    }
-
+/// Append a wallpaper category block to the hyprpaper config content
+fn append_wallpaper(content: &str, monitor: &str, path: &str, fit_mode: Option<String>) -> String {
     let fit = fit_mode.unwrap_or_else(|| "cover".to_string());
-    let home_dir =
-        std::env::var("HOME").map_err(|_| "Could not determine home directory".to_string())?;
-    let config_path = Path::new(&home_dir).join(".config/hypr/hyprpaper.conf");
 
     // Build wallpaper category content
     let wallpaper_block = format!(
@@ -104,70 +100,161 @@ pub fn set_wallpaper(monitor: String, path: String, fit_mode: Option<String>) ->
         fit
     );
 
-    // Append to config file (or create if doesn't exist)
-    let mut content = fs::read_to_string(&config_path).unwrap_or_default();
-    content.push_str(&wallpaper_block);
-    fs::write(&config_path, content)
-        .map_err(|e| format!("Failed to write config file: {}", e))?;
-
-    Ok(())
+    format!("{}{}", content, wallpaper_block)
 }
 
-#[tauri::command]
-pub fn remove_wallpaper(name: String) -> Result<(), String> {
-    let home_dir =
-        std::env::var("HOME").map_err(|_| "Could not determine home directory".to_string())?;
-    let config_path = Path::new(&home_dir).join(".config/hypr/hyprpaper.conf");
-
+/// Render the hyprpaper config with a wallpaper category instance removed by name
+fn remove_wallpaper_instance(config_path: &Path, name: &str) -> Result<String, BackendError> {
     if !config_path.exists() {
-        return Err("Hyprpaper config file not found".to_string());
+        return Err(BackendError::config_not_found(config_path));
     }
 
     let mut config = Config::new();
     register_hyprpaper_config(&mut config);
     config
-        .parse_file(&config_path)
-        .map_err(|e| format!("Failed to parse hyprpaper config: {:?}", e))?;
+        .parse_file(config_path)
+        .map_err(|e| BackendError::parse_failed(config_path, e))?;
 
     // Remove wallpaper special category instance by name
     config
-        .remove_special_category_instance("wallpaper", &name)
-        .map_err(|e| format!("Failed to remove wallpaper: {:?}", e))?;
+        .remove_special_category_instance("wallpaper", name)
+        .map_err(|e| {
+            BackendError::not_found(format!("Wallpaper '{}' not found", name))
+                .with_cause(format!("{:?}", e))
+        })?;
 
-    config
-        .save_as(&config_path)
-        .map_err(|e| format!("Failed to save config file: {:?}", e))?;
+    Ok(config.serialize())
+}
 
-    Ok(())
+/// Write new hyprpaper config content, or only diff it against the file for a dry run.
+/// `before` is the content the new one was derived from; the write is refused with a
+/// conflict when the file no longer matches it.
+fn commit_hyprpaper_config(
+    journal: &Journal,
+    label: &str,
+    config_path: &Path,
+    before: Option<String>,
+    content: String,
+    dry_run: Option<bool>,
+) -> Result<Vec<FileDiff>, BackendError> {
+    let changes = if dry_run.unwrap_or(false) {
+        if before.as_deref() == Some(content.as_str()) {
+            Vec::new()
+        } else {
+            vec![FileChange {
+                path: config_path.to_path_buf(),
+                before,
+                after: Some(content),
+            }]
+        }
+    } else {
+        journal.track(
+            Subsystem::Hyprpaper,
+            label,
+            &[config_path.to_path_buf()],
+            || {
+                let expected_hash = before.as_deref().map(content_hash);
+                write_atomic_checked(config_path, &content, expected_hash)
+            },
+        )?
+    };
+
+    Ok(diff_changes(&changes))
+}
+
+#[tauri::command]
+pub fn set_wallpaper(
+    journal: State<'_, Journal>,
+    monitor: String,
+    path: String,
+    fit_mode: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<Vec<FileDiff>, BackendError> {
+    // Validate path
+    if path.trim().is_empty() {
+        return Err(BackendError::invalid_input("Path cannot be empty"));
+    }
+
+    let config_path = get_hyprpaper_config_path()?;
+
+    // Append to config file (or create if doesn't exist)
+    let before = fs::read_to_string(&config_path).ok();
+    let content = append_wallpaper(before.as_deref().unwrap_or(""), &monitor, &path, fit_mode);
+
+    commit_hyprpaper_config(
+        &journal,
+        "Set wallpaper",
+        &config_path,
+        before,
+        content,
+        dry_run,
+    )
+}
+
+#[tauri::command]
+pub fn remove_wallpaper(
+    journal: State<'_, Journal>,
+    name: String,
+    dry_run: Option<bool>,
+) -> Result<Vec<FileDiff>, BackendError> {
+    let config_path = get_hyprpaper_config_path()?;
+    let before = fs::read_to_string(&config_path).ok();
+    let content = remove_wallpaper_instance(&config_path, &name)?;
+
+    commit_hyprpaper_config(
+        &journal,
+        "Remove wallpaper",
+        &config_path,
+        before,
+        content,
+        dry_run,
+    )
 }
 
 #[tauri::command]
 pub fn update_wallpaper(
+    journal: State<'_, Journal>,
     name: String,
     monitor: String,
     path: String,
     fit_mode: Option<String>,
-) -> Result<(), String> {
+    dry_run: Option<bool>,
+) -> Result<Vec<FileDiff>, BackendError> {
     if path.trim().is_empty() {
-        return Err("Path cannot be empty".to_string());
+        return Err(BackendError::invalid_input("Path cannot be empty"));
     }
 
-    // Remove old and add new (simpler than trying to update in place)
-    remove_wallpaper(name)?;
-    set_wallpaper(monitor, path, fit_mode)?;
+    let config_path = get_hyprpaper_config_path()?;
 
-    Ok(())
+    // Remove old and add new (simpler than trying to update in place)
+    let before = fs::read_to_string(&config_path).ok();
+    let content = remove_wallpaper_instance(&config_path, &name)?;
+    let content = append_wallpaper(&content, &monitor, &path, fit_mode);
+
+    commit_hyprpaper_config(
+        &journal,
+        "Update wallpaper",
+        &config_path,
+        before,
+        content,
+        dry_run,
+    )
 }
 
 #[tauri::command]
-pub fn replace_wallpaper(monitor: String, path: String, fit_mode: Option<String>) -> Result<(), String> {
+pub fn replace_wallpaper(
+    journal: State<'_, Journal>,
+    monitor: String,
+    path: String,
+    fit_mode: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<Vec<FileDiff>, BackendError> {
     if path.trim().is_empty() {
-        return Err("Path cannot be empty".to_string());
+        return Err(BackendError::invalid_input("Path cannot be empty"));
     }
 
-    let home_dir =
-        std::env::var("HOME").map_err(|_| "Could not determine home directory".to_string())?;
-    let config_path = Path::new(&home_dir).join(".config/hypr/hyprpaper.conf");
+    let config_path = get_hyprpaper_config_path()?;
+    let before = fs::read_to_string(&config_path).ok();
 
     let monitor_str = monitor.trim();
     let path_str = path.trim();
@@ -179,10 +266,25 @@ pub fn replace_wallpaper(monitor: String, path: String, fit_mode: Option<String>
         monitor_str, path_str, fit
     );
 
-    fs::write(&config_path, content).map_err(|e| format!("Failed to write config file: {}", e))?;
+    let diffs = commit_hyprpaper_config(
+        &journal,
+        "Replace wallpaper",
+        &config_path,
+        before,
+        content,
+        dry_run,
+    )?;
+
+    // A dry run must not touch the running hyprpaper either
+    if dry_run.unwrap_or(false) {
+        return Ok(diffs);
+    }
 
     // Use new IPC format: hyprctl hyprpaper wallpaper '[mon], [path], [fit_mode]'
-    let command = format!("hyprctl hyprpaper wallpaper '{}, {}, {}'", monitor_str, path_str, fit);
+    let command = format!(
+        "hyprctl hyprpaper wallpaper '{}, {}, {}'",
+        monitor_str, path_str, fit
+    );
 
     let hyprpaper_result = hyprland::dispatch::Dispatch::call(DispatchType::Exec(&command));
 
@@ -191,5 +293,5 @@ pub fn replace_wallpaper(monitor: String, path: String, fit_mode: Option<String>
         Err(e) => println!("Failed to send Hyprpaper wallpaper command: {:?}", e),
     }
 
-    Ok(())
+    Ok(diffs)
 }
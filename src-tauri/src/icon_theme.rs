@@ -0,0 +1,446 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager};
+
+use crate::app_catalog::{DesktopApp, load_applications};
+use crate::backend_error::BackendError;
+use crate::config_paths::{
+    home_dir, xdg_config_dirs, xdg_config_home, xdg_data_dirs, xdg_data_home,
+};
+
+pub const DEFAULT_ICON_SIZE: u32 = 48;
+const FALLBACK_THEME: &str = "hicolor";
+const ICON_EXTENSIONS: &[&str] = &["png", "svg", "xpm"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DirKind {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+/// A subdirectory listed in `index.theme`
+#[derive(Debug, Clone)]
+struct ThemeDir {
+    path: String,
+    size: u32,
+    scale: u32,
+    kind: DirKind,
+    min_size: u32,
+    max_size: u32,
+    threshold: u32,
+}
+
+impl ThemeDir {
+    fn matches_size(&self, size: u32, scale: u32) -> bool {
+        if self.scale != scale {
+            return false;
+        }
+        match self.kind {
+            DirKind::Fixed => self.size == size,
+            DirKind::Scalable => (self.min_size..=self.max_size).contains(&size),
+            DirKind::Threshold => (self.size.saturating_sub(self.threshold)
+                ..=self.size + self.threshold)
+                .contains(&size),
+        }
+    }
+
+    fn size_distance(&self, size: u32, scale: u32) -> u32 {
+        let wanted = size * scale;
+        let (min, max) = match self.kind {
+            DirKind::Fixed => (self.size, self.size),
+            DirKind::Scalable => (self.min_size, self.max_size),
+            DirKind::Threshold => (
+                self.size.saturating_sub(self.threshold),
+                self.size + self.threshold,
+            ),
+        };
+
+        if wanted < min * self.scale {
+            min * self.scale - wanted
+        } else {
+            wanted.saturating_sub(max * self.scale)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Theme {
+    /// The theme's directory in every base dir that has it
+    roots: Vec<PathBuf>,
+    dirs: Vec<ThemeDir>,
+    inherits: Vec<String>,
+}
+
+/// Directories icon themes live in: ~/.icons, then `icons` in every XDG data dir
+fn base_dirs() -> Vec<PathBuf> {
+    home_dir()
+        .map(|home| home.join(".icons"))
+        .into_iter()
+        .chain(xdg_data_home().map(|dir| dir.join("icons")))
+        .chain(xdg_data_dirs().into_iter().map(|dir| dir.join("icons")))
+        .collect()
+}
+
+/// Directories searched for unthemed icons after every theme failed
+fn fallback_dirs() -> Vec<PathBuf> {
+    let mut dirs = base_dirs();
+    dirs.push(PathBuf::from("/usr/share/pixmaps"));
+    dirs
+}
+
+/// Let the asset protocol serve every directory icons are looked up in. They follow
+/// `$XDG_DATA_DIRS`, so tauri.conf.json can't list them.
+pub fn allow_icon_dirs(app: &AppHandle) {
+    let scope = app.asset_protocol_scope();
+    for dir in fallback_dirs().into_iter().filter(|dir| dir.is_dir()) {
+        // Distributions like NixOS link the data dirs elsewhere, so allow where the
+        // link points as well
+        let mut dirs = vec![dir.clone()];
+        dirs.extend(fs::canonicalize(&dir).ok().filter(|c| *c != dir));
+
+        for dir in dirs {
+            if let Err(e) = scope.allow_directory(&dir, true) {
+                eprintln!("Failed to allow icons in {}: {}", dir.display(), e);
+            }
+        }
+    }
+}
+
+/// Whether the asset protocol serves `path`: it lies in an icon directory, or below
+/// $HOME which tauri.conf.json allows
+fn is_servable(path: &Path) -> bool {
+    fallback_dirs()
+        .into_iter()
+        .chain(home_dir().ok())
+        .any(|dir| path.starts_with(dir))
+}
+
+/// The `[section]`s of an ini style file, each as a key to value map
+fn parse_ini(contents: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current = String::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = name.to_string();
+        } else if let Some((key, value)) = line.split_once('=')
+            && !line.starts_with('#')
+        {
+            sections
+                .entry(current.clone())
+                .or_default()
+                .insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    sections
+}
+
+fn split_list(value: Option<&String>) -> Vec<String> {
+    value
+        .map(|v| {
+            v.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Read the `index.theme` of `name` from the first base dir that has one
+fn load_theme(name: &str, base_dirs: &[PathBuf]) -> Option<Theme> {
+    let roots: Vec<PathBuf> = base_dirs
+        .iter()
+        .map(|dir| dir.join(name))
+        .filter(|dir| dir.is_dir())
+        .collect();
+    let index = roots
+        .iter()
+        .find_map(|root| fs::read_to_string(root.join("index.theme")).ok())?;
+    let sections = parse_ini(&index);
+    let theme = sections.get("Icon Theme")?;
+
+    let mut names = split_list(theme.get("Directories"));
+    names.extend(split_list(theme.get("ScaledDirectories")));
+
+    let dirs = names
+        .into_iter()
+        .filter_map(|path| {
+            let section = sections.get(&path)?;
+            let number = |key: &str| section.get(key).and_then(|v| v.parse::<u32>().ok());
+            let size = number("Size")?;
+
+            Some(ThemeDir {
+                size,
+                scale: number("Scale").unwrap_or(1),
+                kind: match section.get("Type").map(String::as_str) {
+                    Some("Fixed") => DirKind::Fixed,
+                    Some("Scalable") => DirKind::Scalable,
+                    _ => DirKind::Threshold,
+                },
+                min_size: number("MinSize").unwrap_or(size),
+                max_size: number("MaxSize").unwrap_or(size),
+                threshold: number("Threshold").unwrap_or(2),
+                path,
+            })
+        })
+        .collect();
+
+    Some(Theme {
+        roots,
+        dirs,
+        inherits: split_list(theme.get("Inherits")),
+    })
+}
+
+/// `gtk-icon-theme-name` from the GTK 4 or GTK 3 settings.ini
+fn settings_ini_theme() -> Option<String> {
+    let user = xdg_config_home().into_iter();
+    let dirs: Vec<PathBuf> = user.chain(xdg_config_dirs()).collect();
+
+    ["gtk-4.0", "gtk-3.0"]
+        .iter()
+        .flat_map(|gtk| {
+            dirs.iter()
+                .map(move |dir| dir.join(gtk).join("settings.ini"))
+        })
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .find_map(|contents| {
+            parse_ini(&contents)
+                .get("Settings")?
+                .get("gtk-icon-theme-name")
+                .map(|name| name.trim_matches('"').to_string())
+                .filter(|name| !name.is_empty())
+        })
+}
+
+/// The icon theme in use: gsettings first, then GTK's settings.ini, then hicolor
+pub fn current_icon_theme() -> String {
+    let gsettings = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "icon-theme"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .trim()
+                .trim_matches('\'')
+                .to_string()
+        })
+        .filter(|name| !name.is_empty());
+
+    gsettings
+        .or_else(settings_ini_theme)
+        .unwrap_or_else(|| FALLBACK_THEME.to_string())
+}
+
+/// Resolves icon names against the current theme, its parents and hicolor. Directory
+/// listings are cached, so resolving many icons with one resolver is cheap.
+pub struct IconResolver {
+    themes: Vec<Theme>,
+    fallback_dirs: Vec<PathBuf>,
+    listings: HashMap<PathBuf, HashSet<String>>,
+}
+
+impl IconResolver {
+    pub fn for_current_theme() -> Self {
+        let base_dirs = base_dirs();
+        let mut themes = Vec::new();
+        let mut seen = HashSet::new();
+
+        // Depth first through the inherits chain, hicolor always last
+        let mut pending = vec![current_icon_theme()];
+        while let Some(name) = pending.pop() {
+            if name == FALLBACK_THEME || !seen.insert(name.clone()) {
+                continue;
+            }
+            if let Some(theme) = load_theme(&name, &base_dirs) {
+                pending.extend(theme.inherits.iter().rev().cloned());
+                themes.push(theme);
+            }
+        }
+        themes.extend(load_theme(FALLBACK_THEME, &base_dirs));
+
+        IconResolver {
+            themes,
+            fallback_dirs: fallback_dirs(),
+            listings: HashMap::new(),
+        }
+    }
+
+    fn file_in(&mut self, dir: &Path, icon: &str) -> Option<PathBuf> {
+        let listing = self.listings.entry(dir.to_path_buf()).or_insert_with(|| {
+            fs::read_dir(dir)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|e| e.file_name().to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+
+        ICON_EXTENSIONS
+            .iter()
+            .map(|ext| format!("{}.{}", icon, ext))
+            .find(|file| listing.contains(file))
+            .map(|file| dir.join(file))
+    }
+
+    /// Exact size matches first, then the directory closest in size
+    fn lookup_in_theme(
+        &mut self,
+        theme: &Theme,
+        icon: &str,
+        size: u32,
+        scale: u32,
+    ) -> Option<PathBuf> {
+        let mut closest: Option<(u32, PathBuf)> = None;
+
+        for dir in &theme.dirs {
+            for root in &theme.roots {
+                let Some(path) = self.file_in(&root.join(&dir.path), icon) else {
+                    continue;
+                };
+                if dir.matches_size(size, scale) {
+                    return Some(path);
+                }
+
+                let distance = dir.size_distance(size, scale);
+                if closest.as_ref().is_none_or(|(best, _)| distance < *best) {
+                    closest = Some((distance, path));
+                }
+            }
+        }
+
+        closest.map(|(_, path)| path)
+    }
+
+    /// Find the file for an icon name, or check an absolute `Icon=` path. Only files the
+    /// asset protocol serves are returned.
+    pub fn find(&mut self, icon: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        let icon = icon.trim();
+        if icon.is_empty() {
+            return None;
+        }
+        if icon.starts_with('/') {
+            let path = PathBuf::from(icon);
+            return (path.is_file() && is_servable(&path)).then_some(path);
+        }
+
+        // Some desktop files name the icon with its extension
+        let icon = ICON_EXTENSIONS
+            .iter()
+            .find_map(|ext| icon.strip_suffix(&format!(".{}", ext)))
+            .unwrap_or(icon);
+
+        let themes = std::mem::take(&mut self.themes);
+        let found = themes
+            .iter()
+            .find_map(|theme| self.lookup_in_theme(theme, icon, size, scale));
+        self.themes = themes;
+
+        found.or_else(|| {
+            self.fallback_dirs
+                .clone()
+                .iter()
+                .find_map(|dir| self.file_in(dir, icon))
+        })
+    }
+}
+
+/// The desktop entry for an app or window class: by `StartupWMClass`, desktop file id
+/// (or its last component, `org.mozilla.firefox` -> `firefox`), name, then program
+pub fn find_desktop_app<'a>(name: &str, apps: &'a [DesktopApp]) -> Option<&'a DesktopApp> {
+    let name = name.trim().to_lowercase();
+    let program = |app: &DesktopApp| {
+        app.exec
+            .split_whitespace()
+            .next()
+            .and_then(|p| Path::new(p).file_name())
+            .map(|p| p.to_string_lossy().to_lowercase())
+    };
+
+    apps.iter()
+        .find(|app| {
+            app.startup_wm_class
+                .as_ref()
+                .is_some_and(|class| class.to_lowercase() == name)
+        })
+        .or_else(|| {
+            apps.iter().find(|app| {
+                let id = app.id.to_lowercase();
+                id == name || id.rsplit('.').next() == Some(name.as_str())
+            })
+        })
+        .or_else(|| apps.iter().find(|app| app.name.to_lowercase() == name))
+        .or_else(|| {
+            apps.iter()
+                .find(|app| program(app).as_deref() == Some(name.as_str()))
+        })
+}
+
+/// Icon path for an application name or window class, via its desktop entry or an
+/// icon named after it
+pub fn app_icon(
+    resolver: &mut IconResolver,
+    apps: &[DesktopApp],
+    name: &str,
+    size: u32,
+    scale: u32,
+) -> Option<String> {
+    find_desktop_app(name, apps)
+        .and_then(|app| app.icon.as_deref())
+        .and_then(|icon| resolver.find(icon, size, scale))
+        .or_else(|| resolver.find(&name.trim().to_lowercase(), size, scale))
+        .map(|path| path.display().to_string())
+}
+
+/// Tauri command resolving icon names, e.g. the `icon` of desktop entries, to files
+/// servable through the asset protocol. Names without an icon map to `None`.
+#[tauri::command]
+pub fn lookup_icons(
+    names: Vec<String>,
+    size: Option<u32>,
+    scale: Option<u32>,
+) -> Result<HashMap<String, Option<String>>, BackendError> {
+    let size = size.unwrap_or(DEFAULT_ICON_SIZE);
+    let scale = scale.unwrap_or(1).max(1);
+    let mut resolver = IconResolver::for_current_theme();
+
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let path = resolver
+                .find(&name, size, scale)
+                .map(|path| path.display().to_string());
+            (name, path)
+        })
+        .collect())
+}
+
+/// Tauri command resolving application names or window classes to icon files
+#[tauri::command]
+pub fn lookup_app_icons(
+    names: Vec<String>,
+    size: Option<u32>,
+    scale: Option<u32>,
+) -> Result<HashMap<String, Option<String>>, BackendError> {
+    let size = size.unwrap_or(DEFAULT_ICON_SIZE);
+    let scale = scale.unwrap_or(1).max(1);
+    let mut resolver = IconResolver::for_current_theme();
+    let apps = load_applications();
+
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let path = app_icon(&mut resolver, &apps, &name, size, scale);
+            (name, path)
+        })
+        .collect())
+}
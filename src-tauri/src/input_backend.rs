@@ -0,0 +1,654 @@
+use hyprland::data::Devices;
+use hyprland::shared::HyprData;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::backend_error::BackendError;
+use crate::config_session::{ConfigSession, MutationResult, TextRewrite};
+use crate::config_targets::resolve_target_file;
+use crate::handler_ids::comment_start;
+use crate::journal_backend::{Journal, Subsystem};
+use crate::option_fields::{Field, FieldKind, field, read_settings, write_settings};
+use crate::source_graph::collect_config_files;
+use crate::xkb_catalog::validate_keyboard_settings;
+
+const INPUT_FIELDS: &[Field] = &[
+    field("kb_model", FieldKind::Text, ""),
+    field("kb_layout", FieldKind::Text, "us"),
+    field("kb_variant", FieldKind::Text, ""),
+    field("kb_options", FieldKind::Text, ""),
+    field("kb_rules", FieldKind::Text, ""),
+    field("kb_file", FieldKind::Text, ""),
+    field("numlock_by_default", FieldKind::Bool, "false"),
+    field("repeat_rate", FieldKind::Int { min: 1, max: 200 }, "25"),
+    field("repeat_delay", FieldKind::Int { min: 0, max: 2000 }, "600"),
+    field(
+        "sensitivity",
+        FieldKind::Float {
+            min: -1.0,
+            max: 1.0,
+        },
+        "0",
+    ),
+    // `custom <step> <points...>` is allowed too, so this isn't a choice
+    field("accel_profile", FieldKind::Text, ""),
+    field("force_no_accel", FieldKind::Bool, "false"),
+    field("left_handed", FieldKind::Bool, "false"),
+    field(
+        "scroll_method",
+        FieldKind::Choice(&["", "2fg", "edge", "on_button_down", "no_scroll"]),
+        "",
+    ),
+    field("natural_scroll", FieldKind::Bool, "false"),
+    field("follow_mouse", FieldKind::Int { min: 0, max: 3 }, "1"),
+    field(
+        "scroll_factor",
+        FieldKind::Float {
+            min: 0.0,
+            max: 10.0,
+        },
+        "1",
+    ),
+];
+
+const TOUCHPAD_FIELDS: &[Field] = &[
+    field("disable_while_typing", FieldKind::Bool, "true"),
+    field("natural_scroll", FieldKind::Bool, "false"),
+    field(
+        "scroll_factor",
+        FieldKind::Float {
+            min: 0.0,
+            max: 10.0,
+        },
+        "1",
+    ),
+    field("middle_button_emulation", FieldKind::Bool, "false"),
+    field("tap_button_map", FieldKind::Choice(&["", "lrm", "lmr"]), ""),
+    field("clickfinger_behavior", FieldKind::Bool, "false"),
+    field("tap-to-click", FieldKind::Bool, "true"),
+    field("drag_lock", FieldKind::Bool, "false"),
+    field("tap-and-drag", FieldKind::Bool, "true"),
+];
+
+/// Options a `device { }` block accepts besides `name`
+const DEVICE_KEYS: &[&str] = &[
+    "sensitivity",
+    "accel_profile",
+    "natural_scroll",
+    "left_handed",
+    "scroll_method",
+    "scroll_button",
+    "scroll_button_lock",
+    "scroll_points",
+    "scroll_factor",
+    "middle_button_emulation",
+    "tap_button_map",
+    "clickfinger_behavior",
+    "tap-to-click",
+    "tap-and-drag",
+    "drag_lock",
+    "disable_while_typing",
+    "flip_x",
+    "flip_y",
+    "kb_model",
+    "kb_layout",
+    "kb_variant",
+    "kb_options",
+    "kb_rules",
+    "kb_file",
+    "numlock_by_default",
+    "resolve_binds_by_sym",
+    "repeat_rate",
+    "repeat_delay",
+    "keybinds",
+    "enabled",
+    "output",
+    "region_position",
+    "region_size",
+    "relative_input",
+    "absolute_region_position",
+    "active_area_size",
+    "active_area_position",
+    "transform",
+];
+
+/// The `input` category, with the values in effect (config or Hyprland's default)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InputSettings {
+    #[serde(default)]
+    pub kb_model: String,
+    pub kb_layout: String,
+    #[serde(default)]
+    pub kb_variant: String,
+    #[serde(default)]
+    pub kb_options: String,
+    #[serde(default)]
+    pub kb_rules: String,
+    #[serde(default)]
+    pub kb_file: String,
+    pub numlock_by_default: bool,
+    pub repeat_rate: i64,
+    pub repeat_delay: i64,
+    pub sensitivity: f64,
+    #[serde(default)]
+    pub accel_profile: String,
+    pub force_no_accel: bool,
+    pub left_handed: bool,
+    #[serde(default)]
+    pub scroll_method: String,
+    pub natural_scroll: bool,
+    pub follow_mouse: i64,
+    pub scroll_factor: f64,
+}
+
+/// The `input:touchpad` subcategory
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TouchpadSettings {
+    pub disable_while_typing: bool,
+    pub natural_scroll: bool,
+    pub scroll_factor: f64,
+    pub middle_button_emulation: bool,
+    #[serde(default)]
+    pub tap_button_map: String,
+    pub clickfinger_behavior: bool,
+    #[serde(rename = "tap-to-click")]
+    pub tap_to_click: bool,
+    pub drag_lock: bool,
+    #[serde(rename = "tap-and-drag")]
+    pub tap_and_drag: bool,
+}
+
+#[tauri::command]
+pub fn get_input_settings(
+    session: State<'_, ConfigSession>,
+) -> Result<InputSettings, BackendError> {
+    read_settings(&session, "input", INPUT_FIELDS)
+}
+
+/// Tauri command writing the input options that differ from the values in effect
+#[tauri::command]
+pub fn set_input_settings(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    settings: InputSettings,
+    dry_run: Option<bool>,
+) -> Result<Vec<MutationResult>, BackendError> {
+    validate_keyboard_settings(
+        Some(&settings.kb_layout),
+        Some(&settings.kb_variant),
+        Some(&settings.kb_options),
+        Some(&settings.kb_rules),
+        Some(&settings.kb_file),
+    )?;
+
+    write_settings(
+        &session,
+        &journal,
+        "input",
+        INPUT_FIELDS,
+        &settings,
+        dry_run,
+    )
+}
+
+#[tauri::command]
+pub fn get_touchpad_settings(
+    session: State<'_, ConfigSession>,
+) -> Result<TouchpadSettings, BackendError> {
+    read_settings(&session, "input:touchpad", TOUCHPAD_FIELDS)
+}
+
+/// Tauri command writing the touchpad options that differ from the values in effect
+#[tauri::command]
+pub fn set_touchpad_settings(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    settings: TouchpadSettings,
+    dry_run: Option<bool>,
+) -> Result<Vec<MutationResult>, BackendError> {
+    write_settings(
+        &session,
+        &journal,
+        "input:touchpad",
+        TOUCHPAD_FIELDS,
+        &settings,
+        dry_run,
+    )
+}
+
+// ==================== DEVICE BLOCKS ====================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeviceOption {
+    pub key: String,
+    pub value: String,
+    /// 1-based line number
+    pub line: usize,
+}
+
+/// A `device { name = ... }` block
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeviceConfig {
+    pub name: String,
+    pub options: Vec<DeviceOption>,
+    pub source_file: String,
+    /// Lines of `device {` and of its closing brace
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceKind {
+    Mouse,
+    Keyboard,
+    Tablet,
+}
+
+/// Layout state Hyprland reports for a keyboard
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyboardState {
+    pub layout: String,
+    pub variant: String,
+    pub options: String,
+    pub active_keymap: String,
+}
+
+/// A device known to the running Hyprland, the config or both
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InputDevice {
+    pub name: String,
+    /// `None` for devices that only appear in the config
+    pub kind: Option<DeviceKind>,
+    pub connected: bool,
+    pub keyboard: Option<KeyboardState>,
+    pub config: Option<DeviceConfig>,
+}
+
+struct Block {
+    name: Option<String>,
+    start: usize,
+    end: usize,
+    options: Vec<DeviceOption>,
+}
+
+/// Find the top-level `device { }` blocks in a file
+fn scan_device_blocks(contents: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut depth = 0;
+    let mut current: Option<Block> = None;
+
+    for (number, line) in contents.lines().enumerate() {
+        let code = line[..comment_start(line).unwrap_or(line.len())].trim();
+
+        if let Some(category) = code.strip_suffix('{') {
+            if depth == 0 && category.trim() == "device" {
+                current = Some(Block {
+                    name: None,
+                    start: number + 1,
+                    end: number + 1,
+                    options: Vec::new(),
+                });
+            }
+            depth += 1;
+            continue;
+        }
+
+        if code == "}" {
+            depth -= 1;
+            if depth == 0
+                && let Some(mut block) = current.take()
+            {
+                block.end = number + 1;
+                blocks.push(block);
+            }
+            continue;
+        }
+
+        if let (1, Some(block), Some((key, value))) =
+            (depth, current.as_mut(), code.split_once('='))
+        {
+            let (key, value) = (key.trim(), value.trim());
+            if key == "name" {
+                block.name = Some(value.to_string());
+            } else {
+                block.options.push(DeviceOption {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    line: number + 1,
+                });
+            }
+        }
+    }
+
+    blocks
+}
+
+/// Every device block in the config; a later block for the same device wins, as in
+/// Hyprland
+fn device_configs(config_path: &Path) -> Vec<(PathBuf, DeviceConfig)> {
+    let hypr_dir = config_path.parent().unwrap_or(Path::new("/"));
+    let mut configs: Vec<(PathBuf, DeviceConfig)> = Vec::new();
+
+    for file in collect_config_files(config_path) {
+        let Ok(contents) = fs::read_to_string(&file) else {
+            continue;
+        };
+
+        for block in scan_device_blocks(&contents) {
+            let Some(name) = block.name else {
+                continue;
+            };
+            configs.retain(|(_, c)| c.name != name);
+            configs.push((
+                file.clone(),
+                DeviceConfig {
+                    name,
+                    options: block.options,
+                    source_file: file
+                        .strip_prefix(hypr_dir)
+                        .unwrap_or(&file)
+                        .display()
+                        .to_string(),
+                    start_line: block.start,
+                    end_line: block.end,
+                },
+            ));
+        }
+    }
+
+    configs
+}
+
+/// Tauri command listing the connected input devices (`hyprctl devices`) merged
+/// with the `device { }` blocks of the config
+#[tauri::command]
+pub fn get_input_devices(
+    session: State<'_, ConfigSession>,
+) -> Result<Vec<InputDevice>, BackendError> {
+    let mut configs = session.read(|_, config_path| Ok(device_configs(config_path)))?;
+    let mut take_config = |name: &str| {
+        let index = configs.iter().position(|(_, c)| c.name == name)?;
+        Some(configs.remove(index).1)
+    };
+
+    let mut devices = Vec::new();
+
+    // Not running Hyprland only means there are no connected devices to show
+    if let Ok(Ok(connected)) = panic::catch_unwind(Devices::get) {
+        for mouse in connected.mice {
+            devices.push(InputDevice {
+                config: take_config(&mouse.name),
+                name: mouse.name,
+                kind: Some(DeviceKind::Mouse),
+                connected: true,
+                keyboard: None,
+            });
+        }
+        for keyboard in connected.keyboards {
+            devices.push(InputDevice {
+                config: take_config(&keyboard.name),
+                name: keyboard.name,
+                kind: Some(DeviceKind::Keyboard),
+                connected: true,
+                keyboard: Some(KeyboardState {
+                    layout: keyboard.layout,
+                    variant: keyboard.variant,
+                    options: keyboard.options,
+                    active_keymap: keyboard.active_keymap,
+                }),
+            });
+        }
+        for tablet in connected.tablets {
+            let Some(name) = tablet.name else {
+                continue;
+            };
+            devices.push(InputDevice {
+                config: take_config(&name),
+                name,
+                kind: Some(DeviceKind::Tablet),
+                connected: true,
+                keyboard: None,
+            });
+        }
+    }
+
+    devices.extend(configs.into_iter().map(|(_, config)| InputDevice {
+        name: config.name.clone(),
+        kind: None,
+        connected: false,
+        keyboard: None,
+        config: Some(config),
+    }));
+
+    Ok(devices)
+}
+
+fn validate_device_name(name: &str) -> Result<(), BackendError> {
+    if name.trim().is_empty() || name.contains(['\n', '#', '{', '}']) {
+        return Err(BackendError::invalid_input(format!(
+            "'{}' is not a valid device name",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn join_lines(lines: &[String], trailing_newline: bool) -> String {
+    let mut contents = lines.join("\n");
+    if trailing_newline {
+        contents.push('\n');
+    }
+    contents
+}
+
+/// Find the block for `name` again inside `contents`, so a rewrite never hits
+/// another block after the file changed
+fn find_block(contents: &str, name: &str, path: &Path) -> Result<Block, BackendError> {
+    scan_device_blocks(contents)
+        .into_iter()
+        .rev()
+        .find(|b| b.name.as_deref() == Some(name))
+        .ok_or_else(|| BackendError::conflict(path))
+}
+
+/// Tauri command setting `key` in the `device { }` block of `device`, or removing it
+/// when `value` is `None`. A device without a block gets one in `target`, or in
+/// hyprland.conf when no target is given.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn set_device_option(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    device: String,
+    key: String,
+    value: Option<String>,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    validate_device_name(&device)?;
+    if !DEVICE_KEYS.contains(&key.as_str()) {
+        return Err(BackendError::invalid_input(format!(
+            "'{}' is not a device option",
+            key
+        )));
+    }
+    let value = value.map(|v| v.trim().to_string());
+    if value.as_ref().is_some_and(|v| v.contains(['\n', '#'])) {
+        return Err(BackendError::invalid_input(
+            "Option values cannot contain newlines or '#'",
+        ));
+    }
+
+    let (config_path, existing) = session.read(|_, config_path| {
+        Ok((
+            config_path.to_path_buf(),
+            device_configs(config_path)
+                .into_iter()
+                .find(|(_, c)| c.name == device),
+        ))
+    })?;
+
+    // Variants are checked against the layout of the block, when it sets one
+    if let Some(value) = &value {
+        let block_value = |name: &str| {
+            existing
+                .as_ref()
+                .and_then(|(_, c)| c.options.iter().rev().find(|o| o.key == name))
+                .map(|o| o.value.as_str())
+        };
+        let value = Some(value.as_str());
+        match key.as_str() {
+            "kb_layout" => validate_keyboard_settings(
+                value,
+                None,
+                None,
+                block_value("kb_rules"),
+                block_value("kb_file"),
+            )?,
+            "kb_variant" => validate_keyboard_settings(
+                block_value("kb_layout"),
+                value,
+                None,
+                block_value("kb_rules"),
+                block_value("kb_file"),
+            )?,
+            "kb_options" => validate_keyboard_settings(
+                None,
+                None,
+                value,
+                block_value("kb_rules"),
+                block_value("kb_file"),
+            )?,
+            _ => {}
+        }
+    }
+    let existing = existing.map(|(path, _)| path);
+
+    let label = match &value {
+        Some(_) => format!("Set {} for {}", key, device),
+        None => format!("Remove {} from {}", key, device),
+    };
+
+    let Some(path) = existing else {
+        let Some(value) = value else {
+            return Err(BackendError::not_found(format!(
+                "{} has no device block",
+                device
+            )));
+        };
+
+        let path = match target.filter(|t| !t.trim().is_empty()) {
+            Some(target) => resolve_target_file(&session, target.trim())?,
+            None => config_path,
+        };
+
+        let rewrite: TextRewrite = Box::new(move |mut contents| {
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            if !contents.is_empty() {
+                contents.push('\n');
+            }
+            contents.push_str(&format!(
+                "device {{\n    name = {}\n    {} = {}\n}}\n",
+                device, key, value
+            ));
+            Ok(contents)
+        });
+        return session.rewrite_file(&journal, Subsystem::Input, &label, dry_run, path, rewrite);
+    };
+
+    let file = path.clone();
+    let rewrite: TextRewrite = Box::new(move |contents| {
+        let block = find_block(&contents, &device, &file)?;
+        let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+        let existing = block.options.iter().rev().find(|o| o.key == key);
+
+        match (existing, value) {
+            (Some(option), Some(value)) => {
+                // Keep indentation and a trailing comment
+                let old = &lines[option.line - 1];
+                let indent = &old[..old.len() - old.trim_start().len()];
+                let comment = comment_start(old)
+                    .map(|i| format!(" {}", &old[i..]))
+                    .unwrap_or_default();
+                lines[option.line - 1] = format!("{}{} = {}{}", indent, key, value, comment);
+            }
+            (Some(option), None) => {
+                lines.remove(option.line - 1);
+            }
+            (None, Some(value)) => {
+                let closing = &lines[block.end - 1];
+                let indent = &closing[..closing.len() - closing.trim_start().len()];
+                let line = format!("{}    {} = {}", indent, key, value);
+                lines.insert(block.end - 1, line);
+            }
+            (None, None) => {}
+        }
+
+        Ok(join_lines(&lines, contents.ends_with('\n')))
+    });
+
+    session.rewrite_file(&journal, Subsystem::Input, &label, dry_run, path, rewrite)
+}
+
+/// Tauri command removing the `device { }` block of `device`
+#[tauri::command]
+pub fn delete_device_config(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    device: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let path = session
+        .read(|_, config_path| {
+            Ok(device_configs(config_path)
+                .into_iter()
+                .find(|(_, c)| c.name == device)
+                .map(|(path, _)| path))
+        })?
+        .ok_or_else(|| BackendError::not_found(format!("{} has no device block", device)))?;
+
+    let label = format!("Remove device config for {}", device);
+    let file = path.clone();
+    let rewrite: TextRewrite = Box::new(move |contents| {
+        let block = find_block(&contents, &device, &file)?;
+        let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+        lines.drain(block.start - 1..block.end);
+
+        Ok(join_lines(&lines, contents.ends_with('\n')))
+    });
+
+    session.rewrite_file(&journal, Subsystem::Input, &label, dry_run, path, rewrite)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_device_blocks_past_escaped_hashes() {
+        let contents = [
+            "device {",
+            "    name = my##mouse # the mouse",
+            "    sensitivity = -0.5 # slower",
+            "    # accel_profile = flat",
+            "}",
+            "# device {",
+        ]
+        .join("\n");
+
+        let blocks = scan_device_blocks(&contents);
+        assert_eq!(blocks.len(), 1);
+        let block = &blocks[0];
+        assert_eq!(block.name.as_deref(), Some("my##mouse"));
+        assert_eq!((block.start, block.end), (1, 5));
+        assert_eq!(block.options.len(), 1);
+        assert_eq!(block.options[0].key, "sensitivity");
+        assert_eq!(block.options[0].value, "-0.5");
+    }
+}
@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+use crate::atomic_write::write_atomic;
+use crate::backend_error::{BackendError, ErrorCode};
+use crate::backup_backend::{FileChange, FileSnapshot, backup_before_write};
+use crate::config_session::ConfigSession;
+use crate::config_validation::{ConfigError, config_errors, errors_after_save};
+
+/// Maximum number of entries kept on the undo stack
+const MAX_JOURNAL_ENTRIES: usize = 100;
+
+/// The part of the configuration a journal entry belongs to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    Keybinds,
+    Bindu,
+    Variables,
+    EnvVars,
+    Monitors,
+    Windowrules,
+    Layerrules,
+    /// Generic handler calls such as exec-once or workspace
+    Handlers,
+    /// Category options such as general:gaps_in
+    Options,
+    /// animation and bezier handler calls
+    Animations,
+    /// input options and device blocks
+    Input,
+    Hyprpaper,
+    Preferences,
+}
+
+/// A recorded mutation with the before and after content of every file it changed
+#[derive(Debug, Clone)]
+struct JournalEntry {
+    id: u64,
+    subsystem: Subsystem,
+    label: String,
+    created_at: u64,
+    changes: Vec<FileChange>,
+}
+
+/// Journal entry as exposed to the frontend
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalEntryInfo {
+    pub id: u64,
+    pub subsystem: Subsystem,
+    pub label: String,
+    pub created_at: u64,
+    pub files: Vec<String>,
+}
+
+impl From<&JournalEntry> for JournalEntryInfo {
+    fn from(entry: &JournalEntry) -> Self {
+        JournalEntryInfo {
+            id: entry.id,
+            subsystem: entry.subsystem,
+            label: entry.label.clone(),
+            created_at: entry.created_at,
+            files: entry
+                .changes
+                .iter()
+                .map(|change| change.path.display().to_string())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JournalState {
+    pub undo: Vec<JournalEntryInfo>,
+    pub redo: Vec<JournalEntryInfo>,
+}
+
+#[derive(Default)]
+struct Stacks {
+    undo: Vec<JournalEntry>,
+    redo: Vec<JournalEntry>,
+    next_id: u64,
+}
+
+/// Undo/redo journal of every config mutation, held in Tauri managed state
+#[derive(Default)]
+pub struct Journal {
+    stacks: Mutex<Stacks>,
+}
+
+impl Journal {
+    fn lock(&self) -> MutexGuard<'_, Stacks> {
+        self.stacks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Back up `paths`, run a write that may touch them, then record whatever it
+    /// changed. The write doesn't run when the backup fails, and nothing is recorded
+    /// when the write fails or leaves every file untouched.
+    pub fn track(
+        &self,
+        subsystem: Subsystem,
+        label: &str,
+        paths: &[PathBuf],
+        write: impl FnOnce() -> Result<(), BackendError>,
+    ) -> Result<Vec<FileChange>, BackendError> {
+        let snapshot = FileSnapshot::capture(paths);
+        snapshot.back_up(label).map_err(|e| BackendError {
+            message: format!("Nothing was saved, the backup failed: {}", e.message),
+            ..e
+        })?;
+
+        write()?;
+        let changes = snapshot.changes();
+
+        if !changes.is_empty() {
+            self.record(subsystem, label, changes.clone());
+        }
+
+        Ok(changes)
+    }
+
+    /// Revert a change that was just tracked and drop it from the journal,
+    /// e.g. because Hyprland rejected the saved config
+    pub fn roll_back(&self, changes: &[FileChange], label: &str) -> Result<(), BackendError> {
+        let mut stacks = self.lock();
+
+        replay(changes, label, false)?;
+
+        if let Some(position) = stacks
+            .undo
+            .iter()
+            .rposition(|entry| entry.changes == changes)
+        {
+            stacks.undo.remove(position);
+        }
+
+        Ok(())
+    }
+
+    fn record(&self, subsystem: Subsystem, label: &str, changes: Vec<FileChange>) {
+        let mut stacks = self.lock();
+
+        stacks.next_id += 1;
+        let entry = JournalEntry {
+            id: stacks.next_id,
+            subsystem,
+            label: label.to_string(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            changes,
+        };
+
+        stacks.undo.push(entry);
+        // A new change invalidates everything that was undone before it
+        stacks.redo.clear();
+
+        if stacks.undo.len() > MAX_JOURNAL_ENTRIES {
+            let overflow = stacks.undo.len() - MAX_JOURNAL_ENTRIES;
+            stacks.undo.drain(..overflow);
+        }
+    }
+}
+
+/// Replace the content of every file in `changes`, going from one side of the change to
+/// the other. Refuses to touch anything when a file was modified since the change.
+fn replay(changes: &[FileChange], label: &str, forward: bool) -> Result<(), BackendError> {
+    let sides = |change: &FileChange| {
+        if forward {
+            (change.before.clone(), change.after.clone())
+        } else {
+            (change.after.clone(), change.before.clone())
+        }
+    };
+
+    // Verify every file first so a conflict doesn't leave the change half applied
+    for change in changes {
+        let (expected, _) = sides(change);
+        if fs::read_to_string(&change.path).ok() != expected {
+            return Err(BackendError::new(
+                ErrorCode::Conflict,
+                format!(
+                    "{:?} was modified outside of hyprconfig after '{}', refusing to overwrite it",
+                    change.path, label
+                ),
+            )
+            .with_file(&change.path));
+        }
+    }
+
+    let paths: Vec<&Path> = changes.iter().map(|change| change.path.as_path()).collect();
+    let action = if forward { "Redo" } else { "Undo" };
+    backup_before_write(&format!("{} {}", action, label), &paths)?;
+
+    for change in changes {
+        let (_, target) = sides(change);
+        match target {
+            Some(contents) => write_atomic(&change.path, &contents)?,
+            None => {
+                if change.path.exists() {
+                    fs::remove_file(&change.path)
+                        .map_err(|e| BackendError::io("remove", &change.path, e))?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_journal(journal: State<'_, Journal>) -> Result<JournalState, BackendError> {
+    let stacks = journal.lock();
+
+    // Most recent first, matching the order undo/redo will walk them
+    Ok(JournalState {
+        undo: stacks
+            .undo
+            .iter()
+            .rev()
+            .map(JournalEntryInfo::from)
+            .collect(),
+        redo: stacks
+            .redo
+            .iter()
+            .rev()
+            .map(JournalEntryInfo::from)
+            .collect(),
+    })
+}
+
+/// Outcome of an undo or redo
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReplayResult {
+    pub entry: JournalEntryInfo,
+    /// Errors Hyprland reported after reloading that weren't there before
+    pub config_errors: Vec<ConfigError>,
+}
+
+/// Move the newest entry of one stack to the other, replaying its changes backwards
+/// for undo or forwards for redo, then reload Hyprland
+fn step(
+    session: &ConfigSession,
+    journal: &Journal,
+    forward: bool,
+) -> Result<Option<ReplayResult>, BackendError> {
+    let errors_before = config_errors();
+
+    // Same lock order as saving: session first, then the journal
+    let entry = session.replace_files(|| {
+        let mut stacks = journal.lock();
+        let Stacks { undo, redo, .. } = &mut *stacks;
+        let (from, to) = if forward { (redo, undo) } else { (undo, redo) };
+
+        let Some(entry) = from.pop() else {
+            return Ok(None);
+        };
+        if let Err(e) = replay(&entry.changes, &entry.label, forward) {
+            from.push(entry);
+            return Err(e);
+        }
+
+        let info = JournalEntryInfo::from(&entry);
+        to.push(entry);
+        Ok(Some(info))
+    })?;
+
+    Ok(entry.map(|entry| ReplayResult {
+        entry,
+        config_errors: errors_after_save(errors_before),
+    }))
+}
+
+#[tauri::command]
+pub fn undo(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+) -> Result<Option<ReplayResult>, BackendError> {
+    step(&session, &journal, false)
+}
+
+#[tauri::command]
+pub fn redo(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+) -> Result<Option<ReplayResult>, BackendError> {
+    step(&session, &journal, true)
+}
@@ -1,13 +1,46 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+mod animations_backend;
+mod app_catalog;
+mod atomic_write;
 mod audio_backend;
+mod backend_error;
+mod backup_backend;
+mod color;
 mod config_backend;
+mod config_diff;
+mod config_drift;
+mod config_paths;
+mod config_session;
+mod config_targets;
+mod config_validation;
+mod decoration_backend;
+mod handler_ids;
+mod handlers_backend;
 mod hyprland_backend;
 mod hyprpaper_backend;
+mod icon_theme;
+mod input_backend;
+mod journal_backend;
+mod option_fields;
+mod options_backend;
+mod options_catalog;
 mod os_backend;
+mod preview_backend;
+mod services_backend;
+mod source_graph;
+mod startup_backend;
+mod xkb_catalog;
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            icon_theme::allow_icon_dirs(app.handle());
+            Ok(())
+        })
+        .manage(config_session::ConfigSession::default())
+        .manage(journal_backend::Journal::default())
+        .manage(preview_backend::Previews::default())
         .invoke_handler(tauri::generate_handler![
             hyprland_backend::get_monitors,
             hyprland_backend::get_network_info,
@@ -40,6 +73,74 @@ fn main() {
             hyprland_backend::get_layerrule_names,
             hyprland_backend::get_layerrule,
             hyprland_backend::delete_layerrule,
+            // generic handler keyword handlers (exec-once, workspace, ...)
+            handlers_backend::get_handler_schemas,
+            handlers_backend::get_handler_keywords,
+            handlers_backend::get_handler_calls,
+            handlers_backend::add_handler_call,
+            handlers_backend::edit_handler_call,
+            handlers_backend::move_handler_call,
+            handlers_backend::delete_handler_call,
+            // category option handlers (general:gaps_in, ...)
+            options_backend::get_option,
+            options_backend::set_option,
+            options_catalog::get_options_catalog,
+            options_catalog::get_option_categories,
+            // animation and bezier handlers
+            animations_backend::get_animations,
+            animations_backend::set_animation,
+            animations_backend::delete_animation,
+            animations_backend::add_bezier,
+            animations_backend::edit_bezier,
+            animations_backend::delete_bezier,
+            animations_backend::sample_bezier,
+            // border color handlers
+            color::get_border_colors,
+            color::set_color_option,
+            // blur and shadow handlers
+            decoration_backend::get_blur_settings,
+            decoration_backend::set_blur_settings,
+            decoration_backend::preview_blur_settings,
+            decoration_backend::get_shadow_settings,
+            decoration_backend::set_shadow_settings,
+            decoration_backend::preview_shadow_settings,
+            // input and device handlers
+            input_backend::get_input_settings,
+            input_backend::set_input_settings,
+            input_backend::get_touchpad_settings,
+            input_backend::set_touchpad_settings,
+            input_backend::get_input_devices,
+            input_backend::set_device_option,
+            input_backend::delete_device_config,
+            // application catalog handlers
+            app_catalog::search_applications,
+            // icon theme handlers
+            icon_theme::lookup_icons,
+            icon_theme::lookup_app_icons,
+            // startup application handlers
+            startup_backend::get_startup_entries,
+            startup_backend::add_startup_entry,
+            startup_backend::edit_startup_entry,
+            startup_backend::set_startup_entry_enabled,
+            startup_backend::move_startup_entry,
+            startup_backend::delete_startup_entry,
+            // systemd user service handlers
+            services_backend::get_user_services,
+            services_backend::service_action,
+            services_backend::get_service_logs,
+            services_backend::create_user_service,
+            // keyboard layout catalog handlers
+            xkb_catalog::get_keyboard_layouts,
+            // runtime vs config drift handlers
+            config_drift::get_config_drift,
+            config_drift::persist_option_drift,
+            config_drift::discard_option_drift,
+            config_drift::discard_monitor_drift,
+            // runtime preview handlers
+            preview_backend::start_preview,
+            preview_backend::confirm_preview,
+            preview_backend::cancel_preview,
+            preview_backend::get_active_preview,
             hyprpaper_backend::get_hyprpaper_config,
             hyprpaper_backend::set_wallpaper,
             hyprpaper_backend::remove_wallpaper,
@@ -49,12 +150,26 @@ fn main() {
             os_backend::run_matugen,
             config_backend::get_preferences,
             config_backend::update_matugen_preferences,
+            config_backend::update_backup_preferences,
+            config_backend::update_validation_preferences,
+            config_backend::update_path_preferences,
+            config_backend::update_target_preferences,
+            config_paths::locate_config,
+            config_targets::list_config_files,
+            source_graph::get_source_tree,
             config_backend::get_theme_css,
             config_backend::ensure_matugen_template,
             audio_backend::get_audio_state,
             audio_backend::set_default_device,
             audio_backend::set_volume,
             audio_backend::set_mute,
+            // backup handlers
+            backup_backend::list_backups,
+            backup_backend::restore_backup,
+            // undo/redo journal handlers
+            journal_backend::get_journal,
+            journal_backend::undo,
+            journal_backend::redo,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,236 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+use tauri::AppHandle;
+
+use crate::backend_error::BackendError;
+use crate::color::parse_color;
+use crate::config_session::{ConfigSession, MutationResult};
+use crate::journal_backend::Journal;
+use crate::options_backend::{
+    OptionValue, option_info, parse_option_value, scan_all_options, write_option,
+};
+use crate::preview_backend::{PreviewChange, PreviewInfo, Previews, begin_preview};
+
+/// How a settings field is typed and validated
+#[derive(Clone, Copy)]
+pub enum FieldKind {
+    Bool,
+    Int {
+        min: i64,
+        max: i64,
+    },
+    Float {
+        min: f64,
+        max: f64,
+    },
+    /// A single color or `$variable`
+    Color,
+    Vec2,
+    /// Free text on one line, `$variables` kept as written
+    Text,
+    /// One of the listed values
+    Choice(&'static [&'static str]),
+}
+
+/// A field of a settings struct, stored as the option `section:name`. Fields
+/// without a default are left out when unset, so the struct needs
+/// `#[serde(default)]` or an `Option` for them.
+pub struct Field {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    /// Hyprland's default as written in the config, empty when there is none
+    pub default: &'static str,
+}
+
+pub const fn field(name: &'static str, kind: FieldKind, default: &'static str) -> Field {
+    Field {
+        name,
+        kind,
+        default,
+    }
+}
+
+/// Convert a parsed config value to the JSON of a settings field. Bools are often
+/// written as 0/1 and floats as integers, so those are coerced.
+fn to_json(kind: FieldKind, value: &OptionValue, raw: &str) -> Option<Value> {
+    match (kind, value) {
+        (FieldKind::Bool, OptionValue::Bool(bool)) => Some(json!(bool)),
+        (FieldKind::Bool, OptionValue::Int(int)) => Some(json!(*int != 0)),
+        (FieldKind::Int { .. }, OptionValue::Int(int)) => Some(json!(int)),
+        (FieldKind::Float { .. }, OptionValue::Int(int)) => Some(json!(*int as f64)),
+        (FieldKind::Float { .. }, OptionValue::Float(float)) => Some(json!(float)),
+        (FieldKind::Vec2, OptionValue::Vec2(pair)) => Some(json!(pair)),
+        // Keep `$variables` as written
+        (FieldKind::Color | FieldKind::Text | FieldKind::Choice(_), _) => Some(json!(raw.trim())),
+        _ => None,
+    }
+}
+
+/// Check a settings field and turn it into the value to write
+fn from_json(section: &str, field: &Field, value: &Value) -> Result<OptionValue, BackendError> {
+    let invalid = |expected: String| {
+        BackendError::invalid_input(format!("{}:{} must be {}", section, field.name, expected))
+    };
+
+    match field.kind {
+        FieldKind::Bool => value
+            .as_bool()
+            .map(OptionValue::Bool)
+            .ok_or_else(|| invalid("true or false".to_string())),
+        FieldKind::Int { min, max } => value
+            .as_i64()
+            .filter(|int| (min..=max).contains(int))
+            .map(OptionValue::Int)
+            .ok_or_else(|| invalid(format!("a whole number from {} to {}", min, max))),
+        FieldKind::Float { min, max } => value
+            .as_f64()
+            .filter(|float| (min..=max).contains(float))
+            .map(OptionValue::Float)
+            .ok_or_else(|| invalid(format!("a number from {} to {}", min, max))),
+        FieldKind::Color => value
+            .as_str()
+            .map(str::trim)
+            .filter(|color| {
+                parse_color(color).is_some()
+                    || color.strip_prefix('$').is_some_and(|name| {
+                        !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                    })
+            })
+            .map(|color| OptionValue::Color(color.to_string()))
+            .ok_or_else(|| invalid("a color such as rgba(1a1a1aee) or a $variable".to_string())),
+        FieldKind::Vec2 => value
+            .as_array()
+            .and_then(|pair| match pair.as_slice() {
+                [x, y] => Some([x.as_f64()?, y.as_f64()?]),
+                _ => None,
+            })
+            .map(OptionValue::Vec2)
+            .ok_or_else(|| invalid("a pair of numbers".to_string())),
+        FieldKind::Text => value
+            .as_str()
+            .filter(|text| !text.contains(['\n', '#']))
+            .map(|text| OptionValue::String(text.trim().to_string()))
+            .ok_or_else(|| invalid("a single line without '#'".to_string())),
+        FieldKind::Choice(choices) => value
+            .as_str()
+            .filter(|choice| choices.contains(choice))
+            .map(|choice| OptionValue::String(choice.to_string()))
+            .ok_or_else(|| invalid(format!("one of {}", choices.join(", ")))),
+    }
+}
+
+/// Read the fields of `section` as JSON, falling back to Hyprland's defaults
+fn read_fields(
+    session: &ConfigSession,
+    section: &str,
+    fields: &[Field],
+) -> Result<Map<String, Value>, BackendError> {
+    session.read(|hypr, config_path| {
+        let mut found = scan_all_options(config_path);
+        let mut values = Map::new();
+
+        for field in fields {
+            let key = format!("{}:{}", section, field.name);
+            let info = option_info(hypr, config_path, &key, found.remove(&key));
+
+            let configured = info
+                .value
+                .as_ref()
+                .zip(info.raw.as_deref())
+                .and_then(|(value, raw)| to_json(field.kind, value, raw));
+            let default = (!field.default.is_empty())
+                .then(|| {
+                    to_json(
+                        field.kind,
+                        &parse_option_value(field.default),
+                        field.default,
+                    )
+                })
+                .flatten();
+
+            if let Some(value) = configured.or(default) {
+                values.insert(field.name.to_string(), value);
+            }
+        }
+
+        Ok(values)
+    })
+}
+
+pub fn read_settings<T: DeserializeOwned>(
+    session: &ConfigSession,
+    section: &str,
+    fields: &[Field],
+) -> Result<T, BackendError> {
+    let values = read_fields(session, section, fields)?;
+    serde_json::from_value(Value::Object(values)).map_err(|e| {
+        BackendError::from(format!("Failed to read {} settings", section)).with_cause(e)
+    })
+}
+
+/// Options whose value in `settings` differs from what is in effect now
+fn changed_options<T: Serialize>(
+    session: &ConfigSession,
+    section: &str,
+    fields: &[Field],
+    settings: &T,
+) -> Result<Vec<(String, OptionValue)>, BackendError> {
+    let current = read_fields(session, section, fields)?;
+    let Ok(Value::Object(wanted)) = serde_json::to_value(settings) else {
+        return Err(BackendError::invalid_input("Settings must be an object"));
+    };
+
+    let mut changes = Vec::new();
+    for field in fields {
+        let Some(value) = wanted.get(field.name).filter(|v| !v.is_null()) else {
+            continue;
+        };
+
+        let value = from_json(section, field, value)?;
+        let unchanged = match current.get(field.name) {
+            Some(current) => from_json(section, field, current).is_ok_and(|c| c == value),
+            // An unset field without a default only matches empty text
+            None => value == OptionValue::String(String::new()),
+        };
+
+        if !unchanged {
+            changes.push((format!("{}:{}", section, field.name), value));
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Write each changed option, every one as its own journal entry
+pub fn write_settings<T: Serialize>(
+    session: &ConfigSession,
+    journal: &Journal,
+    section: &str,
+    fields: &[Field],
+    settings: &T,
+    dry_run: Option<bool>,
+) -> Result<Vec<MutationResult>, BackendError> {
+    changed_options(session, section, fields, settings)?
+        .into_iter()
+        .map(|(key, value)| write_option(session, journal, key, &value, None, dry_run))
+        .collect()
+}
+
+pub fn preview_settings<T: Serialize>(
+    app: AppHandle,
+    session: &ConfigSession,
+    previews: &Previews,
+    section: &str,
+    fields: &[Field],
+    settings: &T,
+    timeout_secs: Option<u64>,
+) -> Result<PreviewInfo, BackendError> {
+    // Colors keep their `$variables`, so confirming writes them as they are
+    let changes = changed_options(session, section, fields, settings)?
+        .into_iter()
+        .map(|(key, value)| PreviewChange::Option { key, value })
+        .collect();
+
+    begin_preview(app, session, previews, changes, timeout_secs)
+}
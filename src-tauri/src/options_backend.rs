@@ -0,0 +1,587 @@
+use hyprlang::Hyprland;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::backend_error::BackendError;
+use crate::color::{is_color, parse_angle, tokenize};
+use crate::config_session::{ConfigSession, MutationResult, TextRewrite};
+use crate::config_targets::resolve_target_file;
+use crate::handler_ids::comment_start;
+use crate::journal_backend::{Journal, Subsystem};
+use crate::source_graph::collect_config_files;
+
+/// Value of a category option such as `general:gaps_in`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum OptionValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// A single color as written, e.g. `rgba(33ccffee)` or `0xff1a1a1a`
+    Color(String),
+    /// Several colors or a color with an angle, e.g. `rgba(33ccffee) rgba(00ff99ee) 45deg`
+    Gradient {
+        colors: Vec<String>,
+        angle: Option<f64>,
+    },
+    Vec2([f64; 2]),
+    String(String),
+}
+
+/// Where an option is defined and what it is set to
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OptionInfo {
+    pub key: String,
+    /// `None` when the config doesn't set the option
+    pub value: Option<OptionValue>,
+    /// The value as written, before `$variables` are expanded
+    pub raw: Option<String>,
+    pub source_file: Option<String>,
+    pub line: Option<usize>,
+}
+
+/// Infer the type of an option from its (variable expanded) value
+pub fn parse_option_value(value: &str) -> OptionValue {
+    let value = value.trim();
+
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" => return OptionValue::Bool(true),
+        "false" | "no" | "off" => return OptionValue::Bool(false),
+        _ => {}
+    }
+
+    if let Ok(int) = value.parse::<i64>() {
+        return OptionValue::Int(int);
+    }
+
+    if let Ok(float) = value.parse::<f64>() {
+        return OptionValue::Float(float);
+    }
+
+    let tokens = tokenize(value);
+
+    if let [x, y] = tokens[..]
+        && let (Ok(x), Ok(y)) = (x.parse::<f64>(), y.parse::<f64>())
+    {
+        return OptionValue::Vec2([x, y]);
+    }
+
+    if let Some((first, rest)) = tokens.split_first()
+        && is_color(first)
+    {
+        let (angle, colors) = match rest.last().and_then(|t| parse_angle(t)) {
+            Some(angle) => (Some(angle), &rest[..rest.len() - 1]),
+            None => (None, rest),
+        };
+
+        if colors.iter().all(|t| is_color(t)) {
+            if colors.is_empty() && angle.is_none() {
+                return OptionValue::Color(first.to_string());
+            }
+
+            return OptionValue::Gradient {
+                colors: tokens[..=colors.len()]
+                    .iter()
+                    .map(|t| t.to_string())
+                    .collect(),
+                angle,
+            };
+        }
+    }
+
+    OptionValue::String(value.to_string())
+}
+
+/// Render a value the way it is written in hyprland.conf
+pub fn format_option_value(value: &OptionValue) -> Result<String, BackendError> {
+    let formatted = match value {
+        OptionValue::Int(int) => int.to_string(),
+        OptionValue::Float(float) => float.to_string(),
+        OptionValue::Bool(bool) => bool.to_string(),
+        OptionValue::Color(color) => color.trim().to_string(),
+        OptionValue::Gradient { colors, angle } => {
+            if colors.is_empty() {
+                return Err(BackendError::invalid_input(
+                    "A gradient needs at least one color",
+                ));
+            }
+            let mut parts: Vec<String> = colors.iter().map(|c| c.trim().to_string()).collect();
+            if let Some(angle) = angle {
+                parts.push(format!("{}deg", angle));
+            }
+            parts.join(" ")
+        }
+        OptionValue::Vec2([x, y]) => format!("{} {}", x, y),
+        OptionValue::String(string) => string.trim().to_string(),
+    };
+
+    if formatted.contains('\n') || formatted.contains('#') {
+        return Err(BackendError::invalid_input(
+            "Option values cannot contain newlines or '#'",
+        ));
+    }
+
+    Ok(formatted)
+}
+
+/// Expand `$variables` in a value the way hyprlang does, longest names first so `$ab`
+/// isn't read as `$a`. Every `$var` lookup goes through here.
+pub(crate) fn expand_variables(value: &str, variables: &HashMap<String, String>) -> String {
+    let mut names: Vec<&String> = variables.keys().collect();
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+
+    names.iter().fold(value.to_string(), |value, name| {
+        value.replace(&format!("${}", name), &variables[*name])
+    })
+}
+
+/// A `key = value` line found by `scan_option`
+#[derive(Debug, Clone)]
+pub struct OptionLine {
+    /// 1-based line number
+    pub line: usize,
+    pub value: String,
+    /// Byte range of the value within the line
+    pub value_range: (usize, usize),
+}
+
+/// Tracks `category {` blocks while walking a config file line by line
+#[derive(Default)]
+pub struct CategoryStack {
+    categories: Vec<String>,
+}
+
+impl CategoryStack {
+    /// Feed one line; returns the full key and the value if it is an assignment
+    pub fn feed<'a>(&mut self, line: &'a str) -> Option<(String, &'a str, usize)> {
+        let code = &line[..comment_start(line).unwrap_or(line.len())];
+        let trimmed = code.trim();
+
+        if let Some(category) = trimmed.strip_suffix('{') {
+            self.categories.push(category.trim().to_string());
+            return None;
+        }
+
+        if trimmed == "}" {
+            self.categories.pop();
+            return None;
+        }
+
+        let (key, value) = code.split_once('=')?;
+        let key = key.trim();
+        if key.is_empty() {
+            return None;
+        }
+
+        let mut full_key = self.categories.join(":");
+        if !full_key.is_empty() {
+            full_key.push(':');
+        }
+        full_key.push_str(key);
+
+        let value_start = key_end(code) + 1 + (value.len() - value.trim_start().len());
+        Some((full_key, value.trim(), value_start))
+    }
+
+    /// Category prefix of keys at the current position, e.g. `decoration:blur`
+    pub fn prefix(&self) -> String {
+        self.categories.join(":")
+    }
+
+    pub fn depth(&self) -> usize {
+        self.categories.len()
+    }
+}
+
+/// Byte offset of the `=` in an assignment line
+fn key_end(code: &str) -> usize {
+    code.find('=').unwrap_or(code.len())
+}
+
+/// Find the last assignment of `key` in `contents`, either inside category blocks
+/// (`general { gaps_in = 5 }`) or written flat (`general:gaps_in = 5`)
+pub fn scan_option(contents: &str, key: &str) -> Option<OptionLine> {
+    let mut stack = CategoryStack::default();
+    let mut found = None;
+
+    for (number, line) in contents.lines().enumerate() {
+        if let Some((full_key, value, start)) = stack.feed(line)
+            && full_key == key
+        {
+            found = Some(OptionLine {
+                line: number + 1,
+                value: value.to_string(),
+                value_range: (start, start + value.len()),
+            });
+        }
+    }
+
+    found
+}
+
+/// Insert `key = value` into `contents`: inside the block for its category when the
+/// file has one at the top level, otherwise as a flat `category:key = value` line
+fn insert_option(contents: &str, key: &str, value: &str) -> String {
+    let (category, name) = key.rsplit_once(':').unwrap_or(("", key));
+
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let mut stack = CategoryStack::default();
+    let mut block_end = None;
+
+    if !category.is_empty() {
+        for (index, line) in lines.iter().enumerate() {
+            let depth = stack.depth();
+            let was_inside = stack.prefix() == category;
+            stack.feed(line);
+            // The block's closing brace is where the depth drops below it
+            if was_inside && stack.depth() < depth {
+                block_end = Some(index);
+                break;
+            }
+        }
+    }
+
+    match block_end {
+        Some(index) => {
+            let closing = &lines[index];
+            let indent = &closing[..closing.len() - closing.trim_start().len()];
+            let line = format!("{}    {} = {}", indent, name, value);
+            lines.insert(index, line);
+        }
+        None => lines.push(format!("{} = {}", key, value)),
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+fn validate_key(key: &str) -> Result<(), BackendError> {
+    let valid = !key.is_empty()
+        && key.split(':').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '-')
+        });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(BackendError::invalid_input(format!(
+            "'{}' is not a valid option key",
+            key
+        )))
+    }
+}
+
+/// Locate the file and line that set `key`. hyprlang knows the file; when it doesn't
+/// track the key every config file is scanned and the last definition wins.
+fn locate_option(hypr: &Hyprland, config_path: &Path, key: &str) -> Option<(PathBuf, OptionLine)> {
+    let scan = |file: &Path| {
+        let contents = fs::read_to_string(file).ok()?;
+        scan_option(&contents, key).map(|line| (file.to_path_buf(), line))
+    };
+
+    match hypr.config().get_key_source_file(key) {
+        Some(file) => scan(file),
+        None => collect_config_files(config_path)
+            .iter()
+            .rev()
+            .find_map(|file| scan(file)),
+    }
+}
+
+/// Every option set in the config, each with the file and line of its last definition
+pub fn scan_all_options(config_path: &Path) -> HashMap<String, (PathBuf, OptionLine)> {
+    let mut options = HashMap::new();
+
+    for file in collect_config_files(config_path) {
+        let Ok(contents) = fs::read_to_string(&file) else {
+            continue;
+        };
+
+        let mut stack = CategoryStack::default();
+        for (number, line) in contents.lines().enumerate() {
+            if let Some((key, value, start)) = stack.feed(line) {
+                let found = OptionLine {
+                    line: number + 1,
+                    value: value.to_string(),
+                    value_range: (start, start + value.len()),
+                };
+                options.insert(key, (file.clone(), found));
+            }
+        }
+    }
+
+    options
+}
+
+/// Build the `OptionInfo` for `key` from where it was found, if anywhere
+pub fn option_info(
+    hypr: &Hyprland,
+    config_path: &Path,
+    key: &str,
+    found: Option<(PathBuf, OptionLine)>,
+) -> OptionInfo {
+    let hypr_dir = config_path.parent().unwrap_or(Path::new("/"));
+
+    let Some((file, found)) = found else {
+        return OptionInfo {
+            key: key.to_string(),
+            value: None,
+            raw: None,
+            source_file: None,
+            line: None,
+        };
+    };
+
+    let expanded = expand_variables(&found.value, hypr.config().variables());
+
+    OptionInfo {
+        key: key.to_string(),
+        value: Some(parse_option_value(&expanded)),
+        raw: Some(found.value),
+        source_file: Some(
+            file.strip_prefix(hypr_dir)
+                .unwrap_or(&file)
+                .display()
+                .to_string(),
+        ),
+        line: Some(found.line),
+    }
+}
+
+#[tauri::command]
+pub fn get_option(
+    session: State<'_, ConfigSession>,
+    key: String,
+) -> Result<OptionInfo, BackendError> {
+    validate_key(&key)?;
+
+    session.read(|hypr, config_path| {
+        let found = locate_option(hypr, config_path, &key);
+        Ok(option_info(hypr, config_path, &key, found))
+    })
+}
+
+/// Tauri command setting a category option. The value is written where the option is
+/// defined; an option the config doesn't set yet is added to `target`, or to
+/// hyprland.conf when no target is given.
+#[tauri::command]
+pub fn set_option(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    key: String,
+    value: OptionValue,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    write_option(&session, &journal, key, &value, target, dry_run)
+}
+
+/// Write `value` for `key`, see `set_option`
+pub fn write_option(
+    session: &ConfigSession,
+    journal: &Journal,
+    key: String,
+    value: &OptionValue,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    validate_key(&key)?;
+    let formatted = format_option_value(value)?;
+    let label = format!("Set {}", key);
+
+    let existing = session.read(|hypr, config_path| {
+        Ok((
+            locate_option(hypr, config_path, &key),
+            config_path.to_path_buf(),
+        ))
+    })?;
+
+    let (path, rewrite): (PathBuf, TextRewrite) = match existing {
+        (Some((file, found)), _) => {
+            let conflict_path = file.clone();
+            let rewrite: TextRewrite = Box::new(move |contents| {
+                // The line must still hold the value that was read
+                let current = scan_option(&contents, &key)
+                    .filter(|line| line.line == found.line && line.value == found.value)
+                    .ok_or_else(|| BackendError::conflict(&conflict_path).with_line(found.line))?;
+
+                let mut lines: Vec<&str> = contents.lines().collect();
+                let old = lines[current.line - 1];
+                let (start, end) = current.value_range;
+                let new = format!("{}{}{}", &old[..start], formatted, &old[end..]);
+                lines[current.line - 1] = &new;
+
+                let mut result = lines.join("\n");
+                if contents.ends_with('\n') {
+                    result.push('\n');
+                }
+                Ok(result)
+            });
+            (file, rewrite)
+        }
+        (None, config_path) => {
+            let path = match target.filter(|t| !t.trim().is_empty()) {
+                Some(target) => resolve_target_file(session, target.trim())?,
+                None => config_path,
+            };
+            let rewrite: TextRewrite =
+                Box::new(move |contents| Ok(insert_option(&contents, &key, &formatted)));
+            (path, rewrite)
+        }
+    };
+
+    session.rewrite_file(journal, Subsystem::Options, &label, dry_run, path, rewrite)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scalars() {
+        assert_eq!(parse_option_value(" 5 "), OptionValue::Int(5));
+        assert_eq!(parse_option_value("-3"), OptionValue::Int(-3));
+        assert_eq!(parse_option_value("0.5"), OptionValue::Float(0.5));
+        assert_eq!(parse_option_value("Yes"), OptionValue::Bool(true));
+        assert_eq!(parse_option_value("off"), OptionValue::Bool(false));
+        assert_eq!(parse_option_value("1 -2.5"), OptionValue::Vec2([1.0, -2.5]));
+        assert_eq!(
+            parse_option_value("dwindle"),
+            OptionValue::String("dwindle".to_string())
+        );
+        assert_eq!(parse_option_value(""), OptionValue::String(String::new()));
+    }
+
+    #[test]
+    fn parses_colors_and_gradients() {
+        assert_eq!(
+            parse_option_value("rgba(33ccffee)"),
+            OptionValue::Color("rgba(33ccffee)".to_string())
+        );
+        assert_eq!(
+            parse_option_value("0xff1a1a1a"),
+            OptionValue::Color("0xff1a1a1a".to_string())
+        );
+        assert_eq!(
+            parse_option_value("rgb(1, 2, 3)"),
+            OptionValue::Color("rgb(1, 2, 3)".to_string())
+        );
+        assert_eq!(
+            parse_option_value("rgba(33ccffee) rgba(00ff99ee) 45deg"),
+            OptionValue::Gradient {
+                colors: vec!["rgba(33ccffee)".to_string(), "rgba(00ff99ee)".to_string()],
+                angle: Some(45.0),
+            }
+        );
+        assert_eq!(
+            parse_option_value("rgb(ffffff) 90deg"),
+            OptionValue::Gradient {
+                colors: vec!["rgb(ffffff)".to_string()],
+                angle: Some(90.0),
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_colors_stay_strings() {
+        for value in [
+            "rgba(33ccffee) nope",
+            "rgba(33ccff)",
+            "rgb(ffffff) 45",
+            "45deg",
+        ] {
+            assert_eq!(
+                parse_option_value(value),
+                OptionValue::String(value.to_string()),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn formats_values() {
+        let cases = [
+            (OptionValue::Int(-3), "-3"),
+            (OptionValue::Float(0.5), "0.5"),
+            (OptionValue::Bool(true), "true"),
+            (OptionValue::Vec2([1.0, 2.5]), "1 2.5"),
+            (OptionValue::Color(" 0xff1a1a1a ".to_string()), "0xff1a1a1a"),
+            (
+                OptionValue::Gradient {
+                    colors: vec!["rgb(ffffff)".to_string(), "$accent".to_string()],
+                    angle: Some(45.0),
+                },
+                "rgb(ffffff) $accent 45deg",
+            ),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(format_option_value(&value).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn formatting_round_trips() {
+        for value in [
+            "7",
+            "0.25",
+            "true",
+            "3 4",
+            "rgba(33ccffee) 0xff000000 -30deg",
+        ] {
+            assert_eq!(
+                format_option_value(&parse_option_value(value)).unwrap(),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unwritable_values() {
+        let rejected = [
+            OptionValue::Gradient {
+                colors: Vec::new(),
+                angle: Some(45.0),
+            },
+            OptionValue::String("a # b".to_string()),
+            OptionValue::String("a\nb".to_string()),
+        ];
+        for value in rejected {
+            assert!(format_option_value(&value).is_err(), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn scans_options_past_escaped_hashes() {
+        let contents = "general {\n    # gaps_in = 1\n    gaps_in = 5 # inner\n}\n\
+                        decoration:screen_shader = ~/a##b.frag # shader\n";
+
+        let found = scan_option(contents, "general:gaps_in").unwrap();
+        assert_eq!((found.line, found.value.as_str()), (3, "5"));
+        assert_eq!(found.value_range, (14, 15));
+
+        let found = scan_option(contents, "decoration:screen_shader").unwrap();
+        assert_eq!(found.value, "~/a##b.frag");
+        let line = contents.lines().nth(found.line - 1).unwrap();
+        assert_eq!(
+            &line[found.value_range.0..found.value_range.1],
+            "~/a##b.frag"
+        );
+    }
+
+    #[test]
+    fn expands_longest_variable_names_first() {
+        let variables = HashMap::from([
+            ("a".to_string(), "1".to_string()),
+            ("ab".to_string(), "2".to_string()),
+        ]);
+        assert_eq!(expand_variables("$ab $a $c", &variables), "2 1 $c");
+    }
+}
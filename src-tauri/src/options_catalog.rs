@@ -0,0 +1,294 @@
+use hyprland::data::Version;
+use hyprland::keyword::{Keyword, OptionValue as RuntimeValue};
+use hyprland::shared::HyprData;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use tauri::State;
+
+use crate::atomic_write::write_atomic;
+use crate::backend_error::BackendError;
+use crate::color::prefix_runtime_colors;
+use crate::config_backend::get_hyprconfig_dir;
+use crate::config_session::ConfigSession;
+use crate::options_backend::{
+    OptionInfo, OptionValue, option_info, parse_option_value, scan_all_options,
+};
+
+/// Option type as reported by `hyprctl descriptions`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionKind {
+    Bool,
+    Int,
+    Float,
+    String,
+    Color,
+    Choice,
+    Gradient,
+    Vector,
+    Unknown,
+}
+
+impl OptionKind {
+    /// Map the numeric type of Hyprland's config descriptions
+    fn from_code(code: i64) -> Self {
+        match code {
+            0 => OptionKind::Bool,
+            1 => OptionKind::Int,
+            2 => OptionKind::Float,
+            3 | 4 => OptionKind::String,
+            5 => OptionKind::Color,
+            6 => OptionKind::Choice,
+            7 => OptionKind::Gradient,
+            8 => OptionKind::Vector,
+            _ => OptionKind::Unknown,
+        }
+    }
+}
+
+/// One entry of `hyprctl descriptions -j`
+#[derive(Debug, Deserialize)]
+struct Description {
+    value: String,
+    #[serde(default)]
+    description: String,
+    #[serde(rename = "type")]
+    kind: i64,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+/// An option Hyprland knows about, joined with what the config and the running
+/// instance say about it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CatalogOption {
+    pub key: String,
+    /// Everything before the last `:`, e.g. `decoration:blur`
+    pub category: String,
+    pub name: String,
+    pub description: String,
+    pub kind: OptionKind,
+    pub default: Option<serde_json::Value>,
+    pub min: Option<serde_json::Value>,
+    pub max: Option<serde_json::Value>,
+    /// Possible values of choice options
+    pub choices: Vec<String>,
+    /// Value set in the config files
+    pub config: OptionInfo,
+    /// Value the running Hyprland uses, `None` when it isn't running
+    pub runtime: Option<OptionValue>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OptionsCatalog {
+    /// Hyprland version the descriptions belong to, `None` when read from an old cache
+    pub version: Option<String>,
+    pub options: Vec<CatalogOption>,
+}
+
+fn cache_dir() -> Result<PathBuf, BackendError> {
+    let dir = get_hyprconfig_dir()?.join("cache");
+    fs::create_dir_all(&dir).map_err(|e| BackendError::io("create", &dir, e))?;
+    Ok(dir)
+}
+
+/// Version string of the running Hyprland, used as the cache key
+fn running_version() -> Option<String> {
+    let version = Version::get().ok()?;
+    let tag = if version.tag.is_empty() {
+        "unknown"
+    } else {
+        &version.tag
+    };
+    Some(format!("{}-{}", tag, version.commit))
+}
+
+fn cache_file(version: &str) -> Result<PathBuf, BackendError> {
+    let name: String = version
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Ok(cache_dir()?.join(format!("descriptions-{}.json", name)))
+}
+
+/// The most recently written descriptions cache, for when Hyprland isn't running
+fn newest_cache() -> Option<PathBuf> {
+    fs::read_dir(cache_dir().ok()?)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("descriptions-"))
+        })
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+}
+
+fn fetch_descriptions() -> Result<String, BackendError> {
+    // The hyprland crate has no wrapper for descriptions, so ask hyprctl directly
+    let output = Command::new("hyprctl")
+        .args(["descriptions", "-j"])
+        .output()
+        .map_err(|e| BackendError::spawn_failed("hyprctl", e))?;
+
+    if !output.status.success() {
+        return Err(BackendError::command_failed("hyprctl", &output.stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Load the descriptions of the running Hyprland, from the cache when this version
+/// was seen before. Without a running instance the newest cache is used.
+fn load_descriptions(refresh: bool) -> Result<(Option<String>, Vec<Description>), BackendError> {
+    let (version, json) = match running_version() {
+        Some(version) => {
+            let path = cache_file(&version)?;
+            let cached = (!refresh).then(|| fs::read_to_string(&path).ok()).flatten();
+
+            let json = match cached {
+                Some(json) => json,
+                None => {
+                    let json = fetch_descriptions()?;
+                    write_atomic(&path, &json)?;
+                    json
+                }
+            };
+            (Some(version), json)
+        }
+        None => {
+            let path = newest_cache().ok_or_else(|| {
+                BackendError::hyprland_not_running("no cached option descriptions")
+            })?;
+            let json = fs::read_to_string(&path).map_err(|e| BackendError::io("read", &path, e))?;
+            (None, json)
+        }
+    };
+
+    let descriptions = serde_json::from_str(&json)
+        .map_err(|e| BackendError::from("Failed to parse hyprctl descriptions").with_cause(e))?;
+
+    Ok((version, descriptions))
+}
+
+/// Choices come as a comma separated string or as an array depending on the version
+fn choices(data: &serde_json::Value) -> Vec<String> {
+    match data.get("options") {
+        Some(serde_json::Value::String(options)) => options
+            .split(',')
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty())
+            .collect(),
+        Some(serde_json::Value::Array(options)) => options
+            .iter()
+            .filter_map(|o| o.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Live value of `key` in the running Hyprland, typed according to `kind`
+fn runtime_value(key: &str, kind: OptionKind) -> Option<OptionValue> {
+    let keyword = Keyword::get(key).ok()?;
+
+    Some(match (keyword.value, kind) {
+        (RuntimeValue::Int(int), OptionKind::Bool) => OptionValue::Bool(int != 0),
+        (RuntimeValue::Int(int), OptionKind::Color) => {
+            OptionValue::Color(format!("0x{:08x}", int as u32))
+        }
+        (RuntimeValue::Int(int), _) => OptionValue::Int(int),
+        (RuntimeValue::Float(float), _) => OptionValue::Float(float),
+        (RuntimeValue::String(string), OptionKind::String | OptionKind::Choice) => {
+            OptionValue::String(string)
+        }
+        // Gradients come back as bare `AARRGGBB` tokens, which can't be written back
+        (RuntimeValue::String(string), _) => parse_option_value(&prefix_runtime_colors(&string)),
+    })
+}
+
+/// Tauri command returning every Hyprland option, or those of one `category`, with
+/// type, default, range, description, config value and runtime value
+#[tauri::command]
+pub fn get_options_catalog(
+    session: State<'_, ConfigSession>,
+    category: Option<String>,
+    refresh: Option<bool>,
+) -> Result<OptionsCatalog, BackendError> {
+    build_catalog(&session, category, refresh.unwrap_or(false))
+}
+
+/// Build the options catalog, see `get_options_catalog`
+pub fn build_catalog(
+    session: &ConfigSession,
+    category: Option<String>,
+    refresh: bool,
+) -> Result<OptionsCatalog, BackendError> {
+    let (version, descriptions) = load_descriptions(refresh)?;
+    let live = version.is_some();
+
+    let descriptions: Vec<Description> = descriptions
+        .into_iter()
+        .filter(|d| match &category {
+            Some(category) => d.value.rsplit_once(':').map(|(c, _)| c) == Some(category.as_str()),
+            None => true,
+        })
+        .collect();
+
+    // Only the config is read under the lock, the runtime values take an IPC call each
+    let mut options = session.read(|hypr, config_path| {
+        let mut found = scan_all_options(config_path);
+
+        Ok(descriptions
+            .into_iter()
+            .map(|d| {
+                let kind = OptionKind::from_code(d.kind);
+                let (category, name) = d.value.rsplit_once(':').unwrap_or(("", &d.value));
+
+                CatalogOption {
+                    category: category.to_string(),
+                    name: name.to_string(),
+                    description: d.description,
+                    kind,
+                    default: d.data.get("default").cloned(),
+                    min: d.data.get("min").cloned(),
+                    max: d.data.get("max").cloned(),
+                    choices: choices(&d.data),
+                    config: option_info(hypr, config_path, &d.value, found.remove(&d.value)),
+                    runtime: None,
+                    key: d.value,
+                }
+            })
+            .collect::<Vec<_>>())
+    })?;
+
+    if live {
+        for option in &mut options {
+            option.runtime = runtime_value(&option.key, option.kind);
+        }
+    }
+
+    Ok(OptionsCatalog { version, options })
+}
+
+/// Tauri command listing the option categories, e.g. `general` or `decoration:blur`
+#[tauri::command]
+pub fn get_option_categories(refresh: Option<bool>) -> Result<Vec<String>, BackendError> {
+    let (_, descriptions) = load_descriptions(refresh.unwrap_or(false))?;
+
+    let mut categories: Vec<String> = descriptions
+        .iter()
+        .filter_map(|d| d.value.rsplit_once(':').map(|(c, _)| c.to_string()))
+        .collect();
+    categories.sort();
+    categories.dedup();
+
+    Ok(categories)
+}
@@ -1,11 +1,13 @@
 use std::process::Command;
 
+use crate::backend_error::BackendError;
+
 #[tauri::command]
-pub fn tool_exists(name: String) -> Result<bool, String> {
+pub fn tool_exists(name: String) -> Result<bool, BackendError> {
     let output = Command::new("which")
         .arg(&name)
         .output()
-        .map_err(|e| format!("Failed to execute which: {}", e))?;
+        .map_err(|e| BackendError::spawn_failed("which", e))?;
 
     Ok(output.status.success())
 }
@@ -15,7 +17,7 @@ pub fn run_matugen(
     image_path: String,
     light_mode: bool,
     generator_type: String,
-) -> Result<(), String> {
+) -> Result<(), BackendError> {
     let mut args = vec!["image", &image_path];
     if light_mode {
         args.push("--mode");
@@ -26,12 +28,11 @@ pub fn run_matugen(
     let output = Command::new("matugen")
         .args(&args)
         .output()
-        .map_err(|e| format!("Failed to execute matugen: {}", e))?;
+        .map_err(|e| BackendError::spawn_failed("matugen", e))?;
 
     if output.status.success() {
         Ok(())
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Matugen failed: {}", stderr))
+        Err(BackendError::command_failed("matugen", &output.stderr))
     }
 }
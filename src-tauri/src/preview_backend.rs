@@ -0,0 +1,387 @@
+use hyprland::data::Monitors;
+use hyprland::keyword::{Keyword, OptionValue as RuntimeValue};
+use hyprland::shared::HyprData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::backend_error::{BackendError, ErrorCode};
+use crate::color::prefix_runtime_colors;
+use crate::config_session::{ConfigSession, MutationResult};
+use crate::hyprland_backend::{hyprctl_keyword, monitor_rule, write_monitor_rule};
+use crate::journal_backend::Journal;
+use crate::options_backend::{OptionValue, expand_variables, format_option_value, write_option};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 15;
+
+/// Event emitted when a preview ran out without being confirmed
+pub const PREVIEW_REVERTED_EVENT: &str = "preview-reverted";
+
+/// A runtime change to try out
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PreviewChange {
+    Option {
+        key: String,
+        value: OptionValue,
+    },
+    Monitor {
+        name: String,
+        width: u16,
+        height: u16,
+        refresh_rate: f32,
+        x: i32,
+        y: i32,
+        scale: f32,
+    },
+}
+
+impl PreviewChange {
+    /// The `hyprctl keyword` call applying the change. hyprctl doesn't know config
+    /// variables, so they are resolved here while the change keeps them.
+    fn keyword(
+        &self,
+        variables: &HashMap<String, String>,
+    ) -> Result<(String, String), BackendError> {
+        match self {
+            PreviewChange::Option { key, value } => Ok((
+                key.clone(),
+                expand_variables(&format_option_value(value)?, variables),
+            )),
+            PreviewChange::Monitor {
+                name,
+                width,
+                height,
+                refresh_rate,
+                x,
+                y,
+                scale,
+            } => Ok((
+                "monitor".to_string(),
+                monitor_rule(name, *width, *height, *refresh_rate, *x, *y, *scale),
+            )),
+        }
+    }
+
+    /// The `hyprctl keyword` call bringing back what the running Hyprland uses now
+    fn current(&self) -> Result<(String, String), BackendError> {
+        match self {
+            PreviewChange::Option { key, .. } => {
+                let keyword = Keyword::get(key).map_err(BackendError::hyprland_not_running)?;
+                Ok((key.clone(), runtime_to_keyword(keyword.value)))
+            }
+            PreviewChange::Monitor { name, .. } => {
+                let monitor = Monitors::get()
+                    .map_err(BackendError::hyprland_not_running)?
+                    .into_iter()
+                    .find(|m| m.name == *name)
+                    .ok_or_else(|| {
+                        BackendError::not_found(format!("Monitor {} not found", name))
+                    })?;
+
+                Ok((
+                    "monitor".to_string(),
+                    monitor_rule(
+                        &monitor.name,
+                        monitor.width,
+                        monitor.height,
+                        monitor.refresh_rate,
+                        monitor.x,
+                        monitor.y,
+                        monitor.scale,
+                    ),
+                ))
+            }
+        }
+    }
+
+    fn persist(
+        &self,
+        session: &ConfigSession,
+        journal: &Journal,
+    ) -> Result<MutationResult, BackendError> {
+        match self {
+            PreviewChange::Option { key, value } => {
+                write_option(session, journal, key.clone(), value, None, None)
+            }
+            PreviewChange::Monitor { name, .. } => {
+                // Monitor rules hold no variables
+                let (_, rule) = self.keyword(&HashMap::new())?;
+                write_monitor_rule(session, journal, name, rule, None)
+            }
+        }
+    }
+}
+
+/// Runtime values come back as numbers, and gradients as bare `AARRGGBB` tokens
+fn runtime_to_keyword(value: RuntimeValue) -> String {
+    match value {
+        RuntimeValue::Int(int) => int.to_string(),
+        RuntimeValue::Float(float) => float.to_string(),
+        RuntimeValue::String(string) => prefix_runtime_colors(&string),
+    }
+}
+
+/// The preview waiting for confirmation
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PreviewInfo {
+    pub id: u64,
+    pub changes: Vec<PreviewChange>,
+    /// Unix time in seconds at which the changes are reverted
+    pub expires_at: u64,
+}
+
+struct ActivePreview {
+    info: PreviewInfo,
+    /// Keyword calls restoring the runtime values from before the preview, in
+    /// the order they must run
+    restore: Vec<(String, String)>,
+    /// The changes are being written to the config, so the timeout must not revert them
+    confirming: bool,
+}
+
+#[derive(Default)]
+struct PreviewSlot {
+    active: Option<ActivePreview>,
+    next_id: u64,
+}
+
+/// At most one preview runs at a time, held in Tauri managed state
+#[derive(Default)]
+pub struct Previews {
+    slot: Mutex<PreviewSlot>,
+}
+
+impl Previews {
+    fn lock(&self) -> MutexGuard<'_, PreviewSlot> {
+        self.slot
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Take the active preview when it is the one with `id` and isn't being confirmed
+    fn take(&self, id: u64) -> Option<ActivePreview> {
+        let mut slot = self.lock();
+        match &slot.active {
+            Some(active) if active.info.id == id && !active.confirming => slot.active.take(),
+            _ => None,
+        }
+    }
+
+    /// Mark preview `id` as being confirmed and return its changes
+    fn start_confirming(&self, id: u64) -> Option<Vec<PreviewChange>> {
+        let mut slot = self.lock();
+        let active = slot
+            .active
+            .as_mut()
+            .filter(|a| a.info.id == id && !a.confirming)?;
+        active.confirming = true;
+        Some(active.info.changes.clone())
+    }
+
+    /// Clear the confirming mark of preview `id`. The preview is taken when it is done
+    /// with: its changes were persisted, or it ran out meanwhile and must be reverted.
+    fn finish_confirming(&self, id: u64, persisted: bool) -> Option<ActivePreview> {
+        let mut slot = self.lock();
+        let active = slot.active.as_mut().filter(|a| a.info.id == id)?;
+        active.confirming = false;
+        if persisted || active.info.expires_at <= now() {
+            slot.active.take()
+        } else {
+            None
+        }
+    }
+}
+
+/// Run every restore call, even when one fails, and report the first failure
+fn revert(restore: &[(String, String)]) -> Result<(), BackendError> {
+    let mut result = Ok(());
+    for (keyword, value) in restore {
+        if let Err(e) = hyprctl_keyword(keyword, value)
+            && result.is_ok()
+        {
+            result = Err(e);
+        }
+    }
+    result
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Tauri command applying `changes` to the running Hyprland and reverting them after
+/// `timeout_secs` unless `confirm_preview` is called first. A preview still waiting is
+/// reverted before the new one starts.
+#[tauri::command]
+pub fn start_preview(
+    app: AppHandle,
+    session: State<'_, ConfigSession>,
+    previews: State<'_, Previews>,
+    changes: Vec<PreviewChange>,
+    timeout_secs: Option<u64>,
+) -> Result<PreviewInfo, BackendError> {
+    begin_preview(app, &session, &previews, changes, timeout_secs)
+}
+
+/// Start a preview, see `start_preview`
+pub fn begin_preview(
+    app: AppHandle,
+    session: &ConfigSession,
+    previews: &Previews,
+    changes: Vec<PreviewChange>,
+    timeout_secs: Option<u64>,
+) -> Result<PreviewInfo, BackendError> {
+    if changes.is_empty() {
+        return Err(BackendError::invalid_input("Nothing to preview"));
+    }
+
+    // Capture everything before applying anything, so a failure leaves no trace
+    let variables = session.read(|hypr, _| Ok(hypr.config().variables().clone()))?;
+    let keywords = changes
+        .iter()
+        .map(|change| change.keyword(&variables))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The runtime values to restore are only known once the old preview is reverted.
+    // hyprctl calls block, so they run without holding the lock.
+    let previous = {
+        let mut slot = previews.lock();
+        if slot.active.as_ref().is_some_and(|a| a.confirming) {
+            return Err(BackendError::new(
+                ErrorCode::Conflict,
+                "The current preview is being saved, try again",
+            ));
+        }
+        slot.active.take()
+    };
+    if let Some(previous) = previous {
+        revert(&previous.restore)?;
+    }
+
+    let mut restore = changes
+        .iter()
+        .map(PreviewChange::current)
+        .collect::<Result<Vec<_>, _>>()?;
+    restore.reverse();
+
+    for (applied, (keyword, value)) in keywords.iter().enumerate() {
+        if let Err(e) = hyprctl_keyword(keyword, value) {
+            let _ = revert(&restore[restore.len() - applied..]);
+            return Err(e);
+        }
+    }
+
+    let timeout = timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS);
+    let mut slot = previews.lock();
+    slot.next_id += 1;
+    let info = PreviewInfo {
+        id: slot.next_id,
+        changes,
+        expires_at: now() + timeout,
+    };
+    slot.active = Some(ActivePreview {
+        info: info.clone(),
+        restore,
+        confirming: false,
+    });
+    drop(slot);
+
+    let id = info.id;
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(timeout)).await;
+
+        let previews = app.state::<Previews>();
+        if let Some(active) = previews.take(id) {
+            let _ = revert(&active.restore);
+            let _ = app.emit(PREVIEW_REVERTED_EVENT, active.info);
+        }
+    });
+
+    Ok(info)
+}
+
+/// Tauri command keeping the changes of preview `id`. With `persist` they are also
+/// written to the config, each as its own journal entry. When writing fails the
+/// preview stays active, so confirming again retries and the timeout still reverts it.
+#[tauri::command]
+pub fn confirm_preview(
+    app: AppHandle,
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    previews: State<'_, Previews>,
+    id: u64,
+    persist: Option<bool>,
+) -> Result<Vec<MutationResult>, BackendError> {
+    let gone = || BackendError::not_found("The preview has already been reverted");
+
+    if !persist.unwrap_or(false) {
+        return previews.take(id).map(|_| Vec::new()).ok_or_else(gone);
+    }
+
+    let changes = previews.start_confirming(id).ok_or_else(gone)?;
+    // Writing the same value again is a no-op, so a retry after a partial failure
+    // only writes what is still missing
+    let result = changes
+        .iter()
+        .map(|change| change.persist(&session, &journal))
+        .collect::<Result<Vec<_>, _>>();
+
+    let finished = previews.finish_confirming(id, result.is_ok());
+    if result.is_err()
+        && let Some(active) = finished
+    {
+        let _ = revert(&active.restore);
+        let _ = app.emit(PREVIEW_REVERTED_EVENT, active.info);
+    }
+    result
+}
+
+/// Tauri command reverting preview `id` right away
+#[tauri::command]
+pub fn cancel_preview(previews: State<'_, Previews>, id: u64) -> Result<(), BackendError> {
+    match previews.take(id) {
+        Some(active) => revert(&active.restore),
+        None => Ok(()),
+    }
+}
+
+/// Tauri command returning the preview waiting for confirmation, if any
+#[tauri::command]
+pub fn get_active_preview(previews: State<'_, Previews>) -> Option<PreviewInfo> {
+    previews.lock().active.as_ref().map(|a| a.info.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_resolve_variables_the_change_keeps() {
+        let variables = HashMap::from([("accent".to_string(), "rgba(33ccffee)".to_string())]);
+        let change = PreviewChange::Option {
+            key: "general:col.active_border".to_string(),
+            value: OptionValue::Gradient {
+                colors: vec!["$accent".to_string(), "0xff000000".to_string()],
+                angle: Some(45.0),
+            },
+        };
+
+        let (key, value) = change.keyword(&variables).unwrap();
+        assert_eq!(key, "general:col.active_border");
+        assert_eq!(value, "rgba(33ccffee) 0xff000000 45deg");
+
+        let PreviewChange::Option { value, .. } = change else {
+            unreachable!()
+        };
+        assert_eq!(
+            format_option_value(&value).unwrap(),
+            "$accent 0xff000000 45deg"
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::atomic_write::write_atomic_checked;
+use crate::backend_error::BackendError;
+use crate::config_paths::xdg_config_home;
+use crate::startup_backend::resolve_program;
+
+const DEFAULT_LOG_LINES: usize = 50;
+const MAX_LOG_LINES: usize = 1000;
+
+/// A `systemctl --user` service unit
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserService {
+    pub unit: String,
+    pub description: String,
+    /// `loaded`, `not-found`, ...; empty for unit files systemd hasn't loaded
+    pub load_state: String,
+    /// `active`, `inactive`, `failed`, ...
+    pub active_state: String,
+    /// `running`, `exited`, `dead`, ...
+    pub sub_state: String,
+    /// `enabled`, `disabled`, `static`, ...; `None` for units without a unit file
+    pub unit_file_state: Option<String>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+    Enable,
+    Disable,
+}
+
+impl ServiceAction {
+    fn verb(self) -> &'static str {
+        match self {
+            ServiceAction::Start => "start",
+            ServiceAction::Stop => "stop",
+            ServiceAction::Restart => "restart",
+            ServiceAction::Enable => "enable",
+            ServiceAction::Disable => "disable",
+        }
+    }
+}
+
+/// Run `systemctl --user` and return its stdout
+fn systemctl(args: &[&str]) -> Result<String, BackendError> {
+    let output = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| BackendError::spawn_failed("systemctl", e))?;
+
+    if !output.status.success() {
+        return Err(BackendError::command_failed(
+            &format!("systemctl --user {}", args.join(" ")),
+            &output.stderr,
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Accept `waybar` or `waybar.service`, rejecting anything that isn't a unit name
+fn service_name(name: &str) -> Result<String, BackendError> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.@:\\".contains(c));
+    if !valid {
+        return Err(BackendError::invalid_input(format!(
+            "'{}' is not a valid unit name",
+            name
+        )));
+    }
+
+    Ok(match name.ends_with(".service") {
+        true => name.to_string(),
+        false => format!("{}.service", name),
+    })
+}
+
+/// Rows of `--plain --no-legend` output; the `●` marker of failed units is dropped
+fn table_rows(output: &str) -> impl Iterator<Item = &str> {
+    output
+        .lines()
+        .map(|line| line.trim_start_matches(['●', '*', ' ']))
+        .filter(|line| !line.is_empty())
+}
+
+fn load_service(unit: &str) -> Result<UserService, BackendError> {
+    let output = systemctl(&[
+        "show",
+        unit,
+        "--property=Id,Description,LoadState,ActiveState,SubState,UnitFileState,FragmentPath",
+    ])?;
+
+    let mut service = UserService {
+        unit: unit.to_string(),
+        description: String::new(),
+        load_state: String::new(),
+        active_state: String::new(),
+        sub_state: String::new(),
+        unit_file_state: None,
+        path: None,
+    };
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.to_string();
+        match key {
+            "Description" => service.description = value,
+            "LoadState" => service.load_state = value,
+            "ActiveState" => service.active_state = value,
+            "SubState" => service.sub_state = value,
+            "UnitFileState" if !value.is_empty() => service.unit_file_state = Some(value),
+            "FragmentPath" if !value.is_empty() => service.path = Some(value),
+            _ => {}
+        }
+    }
+
+    if service.load_state == "not-found" && service.unit_file_state.is_none() {
+        return Err(BackendError::not_found(format!("{} does not exist", unit)));
+    }
+    Ok(service)
+}
+
+/// Tauri command listing user services, loaded ones and installed unit files.
+/// Templates such as `foo@.service` are left out.
+#[tauri::command]
+pub fn get_user_services() -> Result<Vec<UserService>, BackendError> {
+    let units = systemctl(&[
+        "list-units",
+        "--type=service",
+        "--all",
+        "--plain",
+        "--no-legend",
+        "--no-pager",
+    ])?;
+    let files = systemctl(&[
+        "list-unit-files",
+        "--type=service",
+        "--plain",
+        "--no-legend",
+        "--no-pager",
+    ])?;
+
+    let mut services: Vec<UserService> = table_rows(&units)
+        .filter_map(|row| {
+            let mut columns = row.split_whitespace();
+            let unit = columns.next()?.to_string();
+            let load_state = columns.next()?.to_string();
+            let active_state = columns.next()?.to_string();
+            let sub_state = columns.next()?.to_string();
+
+            Some(UserService {
+                unit,
+                description: columns.collect::<Vec<_>>().join(" "),
+                load_state,
+                active_state,
+                sub_state,
+                unit_file_state: None,
+                path: None,
+            })
+        })
+        .collect();
+
+    for row in table_rows(&files) {
+        let mut columns = row.split_whitespace();
+        let (Some(unit), Some(state)) = (columns.next(), columns.next()) else {
+            continue;
+        };
+        if unit.ends_with("@.service") {
+            continue;
+        }
+
+        match services.iter_mut().find(|s| s.unit == unit) {
+            Some(service) => service.unit_file_state = Some(state.to_string()),
+            None => services.push(UserService {
+                unit: unit.to_string(),
+                description: String::new(),
+                load_state: String::new(),
+                active_state: "inactive".to_string(),
+                sub_state: "dead".to_string(),
+                unit_file_state: Some(state.to_string()),
+                path: None,
+            }),
+        }
+    }
+
+    services.sort_by(|a, b| a.unit.cmp(&b.unit));
+    Ok(services)
+}
+
+/// Tauri command starting, stopping, restarting, enabling or disabling a user service.
+/// With `now`, enable and disable also start or stop it. Returns the new state.
+#[tauri::command]
+pub fn service_action(
+    unit: String,
+    action: ServiceAction,
+    now: Option<bool>,
+) -> Result<UserService, BackendError> {
+    let unit = service_name(&unit)?;
+
+    let mut args = vec![action.verb()];
+    if now.unwrap_or(false) && matches!(action, ServiceAction::Enable | ServiceAction::Disable) {
+        args.push("--now");
+    }
+    args.push(&unit);
+    systemctl(&args)?;
+
+    load_service(&unit)
+}
+
+/// Tauri command returning the last `lines` journal lines of a user service
+#[tauri::command]
+pub fn get_service_logs(unit: String, lines: Option<usize>) -> Result<Vec<String>, BackendError> {
+    let unit = service_name(&unit)?;
+    let lines = lines.unwrap_or(DEFAULT_LOG_LINES).clamp(1, MAX_LOG_LINES);
+
+    let output = Command::new("journalctl")
+        .arg(format!("--user-unit={}", unit))
+        .args(["--no-pager", "--output=short-iso", "--lines"])
+        .arg(lines.to_string())
+        .output()
+        .map_err(|e| BackendError::spawn_failed("journalctl", e))?;
+
+    if !output.status.success() {
+        return Err(BackendError::command_failed("journalctl", &output.stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.starts_with("-- "))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Escape `%` so systemd doesn't expand it as a specifier, as in `date +%H`
+fn escape_specifiers(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
+/// The program of an `ExecStart=` line, quoted when the path has whitespace
+fn quote_exec_path(path: &Path) -> String {
+    let path = path.display().to_string();
+    if path.contains(char::is_whitespace) {
+        format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        path
+    }
+}
+
+/// Tauri command writing a user service that runs `command` with the graphical
+/// session, then optionally enabling and starting it. The program is resolved on
+/// PATH, as systemd doesn't search the user's PATH.
+#[tauri::command]
+pub fn create_user_service(
+    name: String,
+    description: String,
+    command: String,
+    enable: Option<bool>,
+    start: Option<bool>,
+) -> Result<UserService, BackendError> {
+    let unit = service_name(&name)?;
+    if unit.contains('@') {
+        return Err(BackendError::invalid_input(
+            "Templates cannot be created here",
+        ));
+    }
+    if description.contains('\n') || command.contains('\n') {
+        return Err(BackendError::invalid_input(
+            "The description and command cannot contain newlines",
+        ));
+    }
+
+    let command = command.trim();
+    let program = command
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| BackendError::invalid_input("The command cannot be empty"))?;
+    let resolved = resolve_program(program).ok_or_else(|| {
+        BackendError::invalid_input(format!("'{}' was not found on PATH", program))
+    })?;
+    let exec_start = escape_specifiers(&format!(
+        "{}{}",
+        quote_exec_path(&resolved),
+        &command[program.len()..]
+    ));
+
+    let description = escape_specifiers(match description.trim() {
+        "" => program,
+        description => description,
+    });
+    let contents = format!(
+        "[Unit]\nDescription={}\nPartOf=graphical-session.target\nAfter=graphical-session.target\n\n\
+         [Service]\nExecStart={}\nRestart=on-failure\n\n\
+         [Install]\nWantedBy=graphical-session.target\n",
+        description, exec_start
+    );
+
+    let dir = xdg_config_home()?.join("systemd/user");
+    fs::create_dir_all(&dir).map_err(|e| BackendError::io("create", &dir, e))?;
+    let path = dir.join(&unit);
+    if path.exists() {
+        return Err(BackendError::invalid_input(format!(
+            "{} already exists",
+            path.display()
+        )));
+    }
+    // `None` also refuses a unit created in the meantime
+    write_atomic_checked(&path, &contents, None)?;
+    systemctl(&["daemon-reload"])?;
+
+    match (enable.unwrap_or(false), start.unwrap_or(false)) {
+        (true, true) => systemctl(&["enable", "--now", &unit])?,
+        (true, false) => systemctl(&["enable", &unit])?,
+        (false, true) => systemctl(&["start", &unit])?,
+        (false, false) => String::new(),
+    };
+
+    load_service(&unit)
+}
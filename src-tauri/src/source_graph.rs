@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::backend_error::BackendError;
+use crate::config_paths::home_dir;
+use crate::config_session::ConfigSession;
+use crate::handler_ids::{comment_start, parse_handler_line};
+use crate::options_backend::expand_variables;
+
+/// Entries a file defines itself, not counting the files it sources
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EntryCounts {
+    pub binds: usize,
+    pub env_vars: usize,
+    pub variables: usize,
+    /// windowrule, windowrulev2 and layerrule lines as well as rule blocks
+    pub rules: usize,
+    pub monitors: usize,
+}
+
+/// A file in the `source =` include tree of hyprland.conf
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SourceNode {
+    pub path: String,
+    /// Path relative to the directory of hyprland.conf when it lies below it
+    pub relative_path: String,
+    /// The `source =` value as written, `None` for hyprland.conf itself
+    pub source: Option<String>,
+    /// Line of the `source =` in the parent file
+    pub line: Option<usize>,
+    pub exists: bool,
+    /// The file already includes itself further up, so it isn't descended into again
+    pub cycle: bool,
+    pub counts: EntryCounts,
+    pub children: Vec<SourceNode>,
+}
+
+/// Walks the include tree in the order Hyprland parses it, so `$VAR` in a
+/// `source =` line sees the variables defined before it
+struct Walker {
+    hypr_dir: PathBuf,
+    variables: HashMap<String, String>,
+    environment: HashMap<String, String>,
+    /// Files currently being visited, to detect cycles
+    ancestors: Vec<PathBuf>,
+}
+
+impl Walker {
+    /// Expand `$VAR` from config variables first, then the environment.
+    /// Unknown variables are left as they are.
+    fn expand_variables(&self, value: &str) -> String {
+        expand_variables(&expand_variables(value, &self.variables), &self.environment)
+    }
+
+    /// Resolve a `source =` value to the files it includes. A glob matching nothing
+    /// yields its pattern, so it shows up as a missing file.
+    fn resolve_source(&self, source: &str, base_dir: &Path) -> Vec<PathBuf> {
+        let expanded = self.expand_variables(source);
+
+        let path = match expanded.strip_prefix("~/") {
+            Some(rest) => home_dir()
+                .map(|home| home.join(rest))
+                .unwrap_or_else(|_| PathBuf::from(&expanded)),
+            None => PathBuf::from(&expanded),
+        };
+        let path = base_dir.join(path);
+
+        if !expanded.contains(['*', '?', '[']) {
+            return vec![path];
+        }
+
+        let mut matches: Vec<PathBuf> = glob::glob(&path.to_string_lossy())
+            .map(|paths| {
+                paths
+                    .filter_map(|p| p.ok())
+                    .filter(|p| p.is_file())
+                    .collect()
+            })
+            .unwrap_or_default();
+        matches.sort();
+
+        if matches.is_empty() {
+            vec![path]
+        } else {
+            matches
+        }
+    }
+
+    fn visit(&mut self, path: &Path, source: Option<(String, usize)>) -> SourceNode {
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let contents = fs::read_to_string(path).ok();
+
+        let mut node = SourceNode {
+            path: path.display().to_string(),
+            relative_path: path
+                .strip_prefix(&self.hypr_dir)
+                .unwrap_or(path)
+                .display()
+                .to_string(),
+            line: source.as_ref().map(|(_, line)| *line),
+            source: source.map(|(value, _)| value),
+            exists: contents.is_some(),
+            cycle: self.ancestors.contains(&canonical),
+            counts: EntryCounts::default(),
+            children: Vec::new(),
+        };
+
+        let Some(contents) = contents.filter(|_| !node.cycle) else {
+            return node;
+        };
+
+        self.ancestors.push(canonical);
+        let base_dir = path.parent().unwrap_or(Path::new("/")).to_path_buf();
+        let mut categories: Vec<String> = Vec::new();
+
+        for (number, line) in contents.lines().enumerate() {
+            let code = line[..comment_start(line).unwrap_or(line.len())].trim();
+
+            if let Some(category) = code.strip_suffix('{') {
+                let category = category.trim().to_string();
+                // windowrule { } and layerrule { } blocks are one rule each
+                if categories.is_empty() {
+                    match category.as_str() {
+                        "windowrule" | "layerrule" => node.counts.rules += 1,
+                        "monitorv2" => node.counts.monitors += 1,
+                        _ => {}
+                    }
+                }
+                categories.push(category);
+                continue;
+            }
+
+            if code == "}" {
+                categories.pop();
+                continue;
+            }
+
+            // Everything below only exists at the top level
+            if !categories.is_empty() {
+                continue;
+            }
+
+            let Some((key, value)) = parse_handler_line(line) else {
+                continue;
+            };
+
+            match key {
+                "source" if !value.is_empty() => {
+                    for child in self.resolve_source(&value, &base_dir) {
+                        let child = self.visit(&child, Some((value.clone(), number + 1)));
+                        node.children.push(child);
+                    }
+                }
+                "env" => node.counts.env_vars += 1,
+                "monitor" => node.counts.monitors += 1,
+                "windowrule" | "windowrulev2" | "layerrule" => node.counts.rules += 1,
+                _ if key.starts_with("bind") => node.counts.binds += 1,
+                _ if key.starts_with('$') => {
+                    node.counts.variables += 1;
+                    self.variables
+                        .insert(key[1..].to_string(), self.expand_variables(&value));
+                }
+                _ => {}
+            }
+        }
+
+        self.ancestors.pop();
+        node
+    }
+}
+
+/// Build the include tree of hyprland.conf from the files on disk
+pub fn source_tree(config_path: &Path) -> SourceNode {
+    let mut walker = Walker {
+        hypr_dir: config_path.parent().unwrap_or(Path::new("/")).to_path_buf(),
+        variables: HashMap::new(),
+        environment: std::env::vars().collect(),
+        ancestors: Vec::new(),
+    };
+
+    walker.visit(config_path, None)
+}
+
+fn flatten(node: &SourceNode, files: &mut Vec<PathBuf>) {
+    let path = PathBuf::from(&node.path);
+    if !node.cycle && !files.contains(&path) {
+        files.push(path);
+    }
+
+    for child in &node.children {
+        flatten(child, files);
+    }
+}
+
+/// Collect hyprland.conf and every file it sources, recursively. Missing files are
+/// included so that creating one is noticed as a change.
+pub fn collect_config_files(config_path: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    flatten(&source_tree(config_path), &mut files);
+    files
+}
+
+/// Tauri command returning the `source =` include tree of hyprland.conf
+#[tauri::command]
+pub fn get_source_tree(session: State<'_, ConfigSession>) -> Result<SourceNode, BackendError> {
+    session.read(|_, config_path| Ok(source_tree(config_path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_sources_past_comments_and_escapes() {
+        let dir = std::env::temp_dir().join(format!("source-graph-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("hyprland.conf");
+        fs::write(
+            &config,
+            "$name = binds # not part of the name\n\
+             source = ./$name.conf # binds\n\
+             source = ./a##b.conf\n\
+             # source = ./commented.conf\n",
+        )
+        .unwrap();
+        fs::write(dir.join("binds.conf"), "bind = SUPER, Q, killactive\n").unwrap();
+        fs::write(dir.join("a#b.conf"), "env = A,1 # env\n").unwrap();
+
+        let tree = source_tree(&config);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(tree.counts.variables, 1);
+        let sources: Vec<_> = tree
+            .children
+            .iter()
+            .map(|child| (child.source.as_deref(), child.exists))
+            .collect();
+        assert_eq!(
+            sources,
+            [(Some("./$name.conf"), true), (Some("./a#b.conf"), true)]
+        );
+        assert_eq!(tree.children[0].counts.binds, 1);
+        assert_eq!(tree.children[1].counts.env_vars, 1);
+    }
+}
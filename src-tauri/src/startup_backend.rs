@@ -0,0 +1,505 @@
+use hyprlang::Hyprland;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::backend_error::{BackendError, ErrorCode};
+use crate::config_paths::expand_home;
+use crate::config_session::{ConfigSession, MutationResult, TextRewrite};
+use crate::handler_ids::{comment_start, handler_entries, make_id, normalize, parse_handler_line};
+use crate::handlers_backend::{
+    check_line, insert_handler_call, join_lines, move_line, remove_handler_call,
+    reorder_handler_call, replace_handler_call,
+};
+use crate::journal_backend::{Journal, Subsystem};
+use crate::options_backend::expand_variables;
+use crate::source_graph::collect_config_files;
+
+const STARTUP_KEYWORDS: &[&str] = &["exec-once", "exec"];
+
+/// An `exec-once` or `exec` line, enabled or commented out
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StartupEntry {
+    /// Stable id to pass to edit/move/delete; enabled entries share it with
+    /// `get_handler_calls`
+    pub id: String,
+    pub keyword: String,
+    pub command: String,
+    /// `false` when the line is commented out
+    pub enabled: bool,
+    pub source_file: Option<String>,
+    pub line: Option<usize>,
+    /// Program the command runs, `None` when it can't be told
+    pub program: Option<String>,
+    /// Where `program` resolves on PATH, `None` when it doesn't
+    pub program_path: Option<String>,
+    /// A process running `program` exists
+    pub running: bool,
+}
+
+/// Where an entry lives, as found when listing
+#[derive(Debug, Clone)]
+struct StartupLine {
+    id: String,
+    keyword: String,
+    command: String,
+    enabled: bool,
+    file: Option<PathBuf>,
+    line: Option<usize>,
+}
+
+/// Split a possibly commented out `exec-once`/`exec` line into enabled, keyword and
+/// command
+fn parse_startup_line(line: &str) -> Option<(bool, &str, String)> {
+    let line = line.trim_start();
+    let (enabled, body) = match line.strip_prefix('#') {
+        Some(body) => (false, body.trim_start_matches('#')),
+        None => (true, line),
+    };
+
+    let (keyword, command) = parse_handler_line(body)?;
+    (STARTUP_KEYWORDS.contains(&keyword) && !command.is_empty())
+        .then_some((enabled, keyword, command))
+}
+
+/// Enabled entries as parsed by hyprlang and commented out ones found in the files,
+/// in file and line order
+fn startup_lines(hypr: &Hyprland, config_path: &Path) -> Vec<StartupLine> {
+    let files = collect_config_files(config_path);
+
+    let mut lines: Vec<StartupLine> = STARTUP_KEYWORDS
+        .iter()
+        .flat_map(|keyword| {
+            handler_entries(hypr, keyword)
+                .into_iter()
+                .map(|entry| StartupLine {
+                    id: entry.id,
+                    keyword: keyword.to_string(),
+                    command: entry.text,
+                    enabled: true,
+                    file: entry.file,
+                    line: entry.line,
+                })
+        })
+        .collect();
+
+    for file in &files {
+        let Ok(contents) = fs::read_to_string(file) else {
+            continue;
+        };
+
+        for (number, line) in contents.lines().enumerate() {
+            let Some((false, keyword, command)) = parse_startup_line(line) else {
+                continue;
+            };
+            lines.push(StartupLine {
+                // Keyed apart from enabled entries so the ids never collide
+                id: make_id(
+                    &format!("#{}", keyword),
+                    Some(file),
+                    Some(number + 1),
+                    &command,
+                ),
+                keyword: keyword.to_string(),
+                command,
+                enabled: false,
+                file: Some(file.clone()),
+                line: Some(number + 1),
+            });
+        }
+    }
+
+    // Entries without a known location go last
+    lines.sort_by_key(|entry| {
+        let file = entry
+            .file
+            .as_ref()
+            .and_then(|f| files.iter().position(|p| p == f))
+            .unwrap_or(usize::MAX);
+        (file, entry.line.unwrap_or(usize::MAX))
+    });
+    lines
+}
+
+fn find_startup_line(session: &ConfigSession, id: &str) -> Result<StartupLine, BackendError> {
+    session.read(|hypr, config_path| {
+        startup_lines(hypr, config_path)
+            .into_iter()
+            .find(|entry| entry.id == id)
+            .ok_or_else(|| {
+                BackendError::new(
+                    ErrorCode::Conflict,
+                    "The startup entry no longer matches the config file",
+                )
+                .with_hint("Reload and try again")
+            })
+    })
+}
+
+fn is_env_assignment(token: &str) -> bool {
+    token.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+    })
+}
+
+/// The program a command runs, skipping `[window rules]`, `env` and `VAR=value`
+fn command_program(command: &str) -> Option<String> {
+    let mut command = command.trim();
+    if command.starts_with('[') {
+        command = command.split_once(']')?.1;
+    }
+
+    command
+        .split_whitespace()
+        .map(|token| token.trim_matches(['"', '\'']))
+        .find(|token| *token != "env" && !is_env_assignment(token))
+        .map(str::to_string)
+}
+
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+/// Look `program` up on PATH, or check it directly when it is a path
+pub fn resolve_program(program: &str) -> Option<PathBuf> {
+    if program.contains('/') {
+        let path = expand_home(program);
+        return is_executable(&path).then_some(path);
+    }
+
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| is_executable(path))
+}
+
+/// Arguments of every process in /proc
+fn process_commands() -> Vec<Vec<String>> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .chars()
+                .all(|c| c.is_ascii_digit())
+        })
+        .filter_map(|entry| fs::read(entry.path().join("cmdline")).ok())
+        .filter(|cmdline| !cmdline.is_empty())
+        .map(|cmdline| {
+            cmdline
+                .split(|byte| *byte == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).to_string())
+                .collect()
+        })
+        .collect()
+}
+
+/// Whether a process runs `program`, directly or as a script passed to an interpreter
+fn is_running(program: &str, processes: &[Vec<String>]) -> bool {
+    let Some(name) = Path::new(program).file_name() else {
+        return false;
+    };
+
+    processes.iter().any(|args| {
+        args.iter()
+            .take(2)
+            .any(|arg| Path::new(arg).file_name() == Some(name))
+    })
+}
+
+fn startup_entry(
+    entry: StartupLine,
+    hypr_dir: &Path,
+    variables: &HashMap<String, String>,
+    processes: &[Vec<String>],
+) -> StartupEntry {
+    let program = command_program(&expand_variables(&entry.command, variables));
+    let program_path = program.as_deref().and_then(resolve_program);
+    let running = program
+        .as_deref()
+        .is_some_and(|program| is_running(program, processes));
+
+    StartupEntry {
+        id: entry.id,
+        keyword: entry.keyword,
+        command: entry.command,
+        enabled: entry.enabled,
+        source_file: entry
+            .file
+            .as_ref()
+            .map(|p| p.strip_prefix(hypr_dir).unwrap_or(p).display().to_string()),
+        line: entry.line,
+        program,
+        program_path: program_path.map(|p| p.display().to_string()),
+        running,
+    }
+}
+
+fn validate_entry(keyword: &str, command: &str) -> Result<(), BackendError> {
+    if !STARTUP_KEYWORDS.contains(&keyword) {
+        return Err(BackendError::invalid_input(format!(
+            "'{}' is not a startup keyword",
+            keyword
+        )));
+    }
+    if command.trim().is_empty() {
+        return Err(BackendError::invalid_input("The command cannot be empty"));
+    }
+    if command.contains(['\n', '#']) {
+        return Err(BackendError::invalid_input(
+            "Commands cannot contain newlines or '#'",
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `text` is the line `entry` was read from
+fn holds_entry(text: &str, entry: &StartupLine) -> bool {
+    parse_startup_line(text).is_some_and(|(enabled, keyword, command)| {
+        enabled == entry.enabled
+            && keyword == entry.keyword
+            && normalize(&command) == normalize(&entry.command)
+    })
+}
+
+/// Render a startup line, keeping the indentation and trailing comment of `old`
+fn format_line(old: &str, enabled: bool, keyword: &str, command: &str) -> String {
+    let indent = &old[..old.len() - old.trim_start().len()];
+    let body = old.trim_start().trim_start_matches('#');
+    let comment = comment_start(body)
+        .map(|i| format!(" {}", &body[i..]))
+        .unwrap_or_default();
+    let prefix = if enabled { "" } else { "# " };
+
+    format!("{}{}{} = {}{}", indent, prefix, keyword, command, comment)
+}
+
+fn located(entry: &StartupLine) -> Result<(PathBuf, usize), BackendError> {
+    match (&entry.file, entry.line) {
+        (Some(file), Some(line)) => Ok((file.clone(), line)),
+        _ => Err(BackendError::not_found(format!(
+            "Could not locate the {} entry in its file",
+            entry.keyword
+        ))),
+    }
+}
+
+/// Replace the line of `entry` with what `change` makes of it, or drop it on `None`
+fn rewrite_entry(
+    session: &ConfigSession,
+    journal: &Journal,
+    label: &str,
+    dry_run: Option<bool>,
+    entry: StartupLine,
+    change: impl FnOnce(&str) -> Option<String> + 'static,
+) -> Result<MutationResult, BackendError> {
+    let (path, line) = located(&entry)?;
+
+    let file = path.clone();
+    let rewrite: TextRewrite = Box::new(move |contents| {
+        let mut lines: Vec<&str> = contents.lines().collect();
+        check_line(&lines, line, &file, |l| holds_entry(l, &entry))?;
+
+        let new = change(lines[line - 1]);
+        match &new {
+            Some(new) => lines[line - 1] = new,
+            None => {
+                lines.remove(line - 1);
+            }
+        }
+
+        Ok(join_lines(&lines, contents.ends_with('\n')))
+    });
+
+    session.rewrite_file(journal, Subsystem::Handlers, label, dry_run, path, rewrite)
+}
+
+/// Tauri command listing every `exec-once` and `exec` entry, commented out ones
+/// included, with whether its program resolves on PATH and is running
+#[tauri::command]
+pub fn get_startup_entries(
+    session: State<'_, ConfigSession>,
+) -> Result<Vec<StartupEntry>, BackendError> {
+    let processes = process_commands();
+
+    session.read(|hypr, config_path| {
+        let hypr_dir = config_path.parent().unwrap_or(Path::new("/"));
+        let variables = hypr.config().variables();
+
+        Ok(startup_lines(hypr, config_path)
+            .into_iter()
+            .map(|entry| startup_entry(entry, hypr_dir, variables, &processes))
+            .collect())
+    })
+}
+
+/// Tauri command adding a startup entry, appended to `target` when given
+#[tauri::command]
+pub fn add_startup_entry(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    keyword: String,
+    command: String,
+    target: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    validate_entry(&keyword, &command)?;
+    insert_handler_call(
+        &session,
+        &journal,
+        &keyword,
+        command.trim().to_string(),
+        target,
+        dry_run,
+    )
+}
+
+/// Tauri command changing the command of an entry, or switching it between
+/// `exec-once` and `exec`. Disabled entries stay commented out.
+#[tauri::command]
+pub fn edit_startup_entry(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    keyword: String,
+    command: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    validate_entry(&keyword, &command)?;
+    let command = command.trim().to_string();
+    let entry = find_startup_line(&session, &id)?;
+
+    // An enabled entry keeping its keyword is an ordinary handler call
+    if entry.enabled && entry.keyword == keyword {
+        return replace_handler_call(&session, &journal, keyword, id, command, dry_run);
+    }
+
+    let label = format!("Edit {}", keyword);
+    let enabled = entry.enabled;
+    rewrite_entry(&session, &journal, &label, dry_run, entry, move |old| {
+        Some(format_line(old, enabled, &keyword, &command))
+    })
+}
+
+/// Tauri command enabling an entry, or disabling it by commenting it out
+#[tauri::command]
+pub fn set_startup_entry_enabled(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    enabled: bool,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let entry = find_startup_line(&session, &id)?;
+    if entry.enabled == enabled {
+        return Err(BackendError::invalid_input(format!(
+            "The {} entry is already {}",
+            entry.keyword,
+            if enabled { "enabled" } else { "disabled" }
+        )));
+    }
+
+    let label = match enabled {
+        true => format!("Enable {}", entry.keyword),
+        false => format!("Disable {}", entry.keyword),
+    };
+    let (keyword, command) = (entry.keyword.clone(), entry.command.clone());
+    rewrite_entry(&session, &journal, &label, dry_run, entry, move |old| {
+        Some(format_line(old, enabled, &keyword, &command))
+    })
+}
+
+/// Tauri command moving an entry right before the entry `before`, or after the last
+/// startup entry of its file when `before` is `None`. Both must be in one file.
+#[tauri::command]
+pub fn move_startup_entry(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    before: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let entry = find_startup_line(&session, &id)?;
+    let anchor = before
+        .as_ref()
+        .map(|before| find_startup_line(&session, before))
+        .transpose()?;
+
+    // Between enabled entries of one keyword this is an ordinary handler call move
+    if entry.enabled
+        && let Some(anchor) = &anchor
+        && anchor.enabled
+        && anchor.keyword == entry.keyword
+    {
+        let keyword = entry.keyword.clone();
+        return reorder_handler_call(&session, &journal, keyword, id, before, dry_run);
+    }
+
+    let (path, line) = located(&entry)?;
+
+    let anchor_line = match &anchor {
+        Some(anchor) => {
+            let (anchor_path, anchor_line) = located(anchor)?;
+            if anchor_path != path {
+                return Err(BackendError::invalid_input(format!(
+                    "Entries can only be moved within one file, {:?}",
+                    path
+                )));
+            }
+            Some(anchor_line)
+        }
+        None => None,
+    };
+
+    let label = format!("Move {}", entry.keyword);
+    let file = path.clone();
+    let rewrite: TextRewrite = Box::new(move |contents| {
+        let mut lines: Vec<&str> = contents.lines().collect();
+        check_line(&lines, line, &file, |l| holds_entry(l, &entry))?;
+        if let (Some(anchor), Some(anchor_line)) = (&anchor, anchor_line) {
+            check_line(&lines, anchor_line, &file, |l| holds_entry(l, anchor))?;
+        }
+
+        // Disabled entries take part, so the last startup line of any kind counts
+        move_line(&mut lines, line, anchor_line, |l| {
+            parse_startup_line(l).is_some()
+        });
+
+        Ok(join_lines(&lines, contents.ends_with('\n')))
+    });
+
+    session.rewrite_file(
+        &journal,
+        Subsystem::Handlers,
+        &label,
+        dry_run,
+        path,
+        rewrite,
+    )
+}
+
+#[tauri::command]
+pub fn delete_startup_entry(
+    session: State<'_, ConfigSession>,
+    journal: State<'_, Journal>,
+    id: String,
+    dry_run: Option<bool>,
+) -> Result<MutationResult, BackendError> {
+    let entry = find_startup_line(&session, &id)?;
+
+    if entry.enabled {
+        return remove_handler_call(&session, &journal, &entry.keyword, &id, dry_run);
+    }
+
+    let label = format!("Delete {}", entry.keyword);
+    rewrite_entry(&session, &journal, &label, dry_run, entry, |_| None)
+}
@@ -0,0 +1,552 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::backend_error::{BackendError, ErrorCode};
+
+/// A model, layout, variant or option with its human readable description
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct XkbItem {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct XkbLayout {
+    pub name: String,
+    pub description: String,
+    pub variants: Vec<XkbItem>,
+}
+
+/// Options sharing a prefix, e.g. `grp` for the layout switching keys
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct XkbOptionGroup {
+    pub name: String,
+    pub description: String,
+    /// More than one option of the group can be set at once
+    pub multiple: bool,
+    pub options: Vec<XkbItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct XkbCatalog {
+    pub models: Vec<XkbItem>,
+    pub layouts: Vec<XkbLayout>,
+    pub option_groups: Vec<XkbOptionGroup>,
+    /// File the catalog was read from
+    pub source: String,
+}
+
+/// Directories holding the XKB rules, `$XKB_CONFIG_ROOT` first
+fn rules_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(root) = std::env::var("XKB_CONFIG_ROOT") {
+        dirs.push(PathBuf::from(root).join("rules"));
+    }
+    dirs.push(PathBuf::from("/usr/share/X11/xkb/rules"));
+    dirs.push(PathBuf::from("/usr/local/share/X11/xkb/rules"));
+    dirs
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Parse `evdev.xml`. The registry has a fixed shape, so a scanner over its tags
+/// is enough: every `configItem` belongs to the element around it.
+fn parse_xml(xml: &str) -> XkbCatalog {
+    let mut catalog = XkbCatalog::default();
+    let mut stack: Vec<String> = Vec::new();
+    let mut text = String::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        // Comments, the declaration and the doctype carry nothing
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let value = unescape(text.trim());
+            text.clear();
+
+            // `name` and `description` of a configItem describe its parent element
+            let owner = match stack.len() {
+                len if len >= 3 && stack[len - 2] == "configItem" => Some(stack[len - 3].as_str()),
+                _ => None,
+            };
+            let item: Option<&mut dyn Describe> = match owner {
+                Some("model") => catalog.models.last_mut().map(|m| m as _),
+                Some("layout") => catalog.layouts.last_mut().map(|l| l as _),
+                Some("variant") => catalog
+                    .layouts
+                    .last_mut()
+                    .and_then(|l| l.variants.last_mut())
+                    .map(|v| v as _),
+                Some("group") => catalog.option_groups.last_mut().map(|g| g as _),
+                Some("option") => catalog
+                    .option_groups
+                    .last_mut()
+                    .and_then(|g| g.options.last_mut())
+                    .map(|o| o as _),
+                _ => None,
+            };
+
+            if let Some(item) = item {
+                match name {
+                    "name" => item.set_name(value),
+                    "description" => item.set_description(value),
+                    _ => {}
+                }
+            }
+
+            stack.pop();
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let element = tag
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        text.clear();
+
+        match element.as_str() {
+            "model" => catalog.models.push(XkbItem::default()),
+            "layout" => catalog.layouts.push(XkbLayout::default()),
+            "variant" => {
+                if let Some(layout) = catalog.layouts.last_mut() {
+                    layout.variants.push(XkbItem::default());
+                }
+            }
+            "group" => catalog.option_groups.push(XkbOptionGroup {
+                multiple: tag.contains("allowMultipleSelection=\"true\""),
+                ..XkbOptionGroup::default()
+            }),
+            "option" => {
+                if let Some(group) = catalog.option_groups.last_mut() {
+                    group.options.push(XkbItem::default());
+                }
+            }
+            _ => {}
+        }
+
+        if !self_closing {
+            stack.push(element);
+        }
+    }
+
+    catalog
+}
+
+/// Common setters for the items a `configItem` can describe
+trait Describe {
+    fn set_name(&mut self, name: String);
+    fn set_description(&mut self, description: String);
+}
+
+macro_rules! impl_describe {
+    ($($ty:ty),*) => {$(
+        impl Describe for $ty {
+            fn set_name(&mut self, name: String) {
+                self.name = name;
+            }
+            fn set_description(&mut self, description: String) {
+                self.description = description;
+            }
+        }
+    )*};
+}
+
+impl_describe!(XkbItem, XkbLayout, XkbOptionGroup);
+
+/// Parse `evdev.lst`, which lists the same data in `! section` blocks. Variants are
+/// written as `name  layout: Description`.
+fn parse_lst(lst: &str) -> XkbCatalog {
+    let mut catalog = XkbCatalog::default();
+    let mut section = "";
+
+    for line in lst.lines() {
+        if let Some(name) = line.strip_prefix('!') {
+            section = name.trim();
+            continue;
+        }
+
+        let line = line.trim();
+        let Some((name, description)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let item = XkbItem {
+            name: name.to_string(),
+            description: description.trim().to_string(),
+        };
+
+        match section {
+            "model" => catalog.models.push(item),
+            "layout" => catalog.layouts.push(XkbLayout {
+                name: item.name,
+                description: item.description,
+                variants: Vec::new(),
+            }),
+            "variant" => {
+                let Some((layout, description)) = item.description.split_once(':') else {
+                    continue;
+                };
+                if let Some(layout) = catalog.layouts.iter_mut().find(|l| l.name == layout) {
+                    layout.variants.push(XkbItem {
+                        name: item.name,
+                        description: description.trim().to_string(),
+                    });
+                }
+            }
+            "option" => match item.name.split_once(':') {
+                Some((group, _)) => {
+                    if let Some(group) = catalog.option_groups.iter_mut().find(|g| g.name == group)
+                    {
+                        group.options.push(item);
+                    }
+                }
+                None => catalog.option_groups.push(XkbOptionGroup {
+                    name: item.name,
+                    description: item.description,
+                    // The list doesn't say, and most groups allow several
+                    multiple: true,
+                    options: Vec::new(),
+                }),
+            },
+            _ => {}
+        }
+    }
+
+    catalog
+}
+
+/// Load the evdev rules, preferring the XML registry over the `.lst` file
+pub fn load_xkb_catalog() -> Result<XkbCatalog, BackendError> {
+    for dir in rules_dirs() {
+        let xml = dir.join("evdev.xml");
+        if let Ok(contents) = fs::read_to_string(&xml) {
+            let catalog = parse_xml(&contents);
+            if !catalog.layouts.is_empty() {
+                return Ok(XkbCatalog {
+                    source: xml.display().to_string(),
+                    ..catalog
+                });
+            }
+        }
+
+        let lst = dir.join("evdev.lst");
+        if let Ok(contents) = fs::read_to_string(&lst) {
+            return Ok(XkbCatalog {
+                source: lst.display().to_string(),
+                ..parse_lst(&contents)
+            });
+        }
+    }
+
+    Err(
+        BackendError::new(ErrorCode::NotFound, "Could not find the XKB rules")
+            .with_hint("Install xkeyboard-config"),
+    )
+}
+
+/// Tauri command returning the keyboard models, layouts with their variants, and
+/// options known to XKB
+#[tauri::command]
+pub fn get_keyboard_layouts() -> Result<XkbCatalog, BackendError> {
+    load_xkb_catalog()
+}
+
+/// Empty values and values using `$variables` are not checked
+fn checkable(value: Option<&str>) -> Option<&str> {
+    value.filter(|v| !v.trim().is_empty() && !v.contains('$'))
+}
+
+fn split_list(value: &str) -> Vec<&str> {
+    value.split(',').map(str::trim).collect()
+}
+
+impl XkbCatalog {
+    /// Check comma separated `kb_layout`, `kb_variant` and `kb_options` values.
+    /// Variants pair up with layouts by position and may be empty.
+    pub fn check(
+        &self,
+        layout: Option<&str>,
+        variant: Option<&str>,
+        options: Option<&str>,
+    ) -> Result<(), BackendError> {
+        let layouts: Vec<&XkbLayout> = match checkable(layout) {
+            Some(layout) => split_list(layout)
+                .into_iter()
+                .map(|name| {
+                    self.layouts.iter().find(|l| l.name == name).ok_or_else(|| {
+                        BackendError::invalid_input(format!("Unknown keyboard layout '{}'", name))
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+
+        if let Some(variant) = checkable(variant)
+            && !layouts.is_empty()
+        {
+            let variants = split_list(variant);
+            if variants.len() > layouts.len() {
+                return Err(BackendError::invalid_input(
+                    "kb_variant has more entries than kb_layout",
+                ));
+            }
+
+            for (name, layout) in variants.iter().zip(&layouts) {
+                if !name.is_empty() && !layout.variants.iter().any(|v| v.name == *name) {
+                    return Err(BackendError::invalid_input(format!(
+                        "Layout '{}' has no variant '{}'",
+                        layout.name, name
+                    )));
+                }
+            }
+        }
+
+        if let Some(options) = checkable(options) {
+            for name in split_list(options).into_iter().filter(|o| !o.is_empty()) {
+                let known = self
+                    .option_groups
+                    .iter()
+                    .flat_map(|g| &g.options)
+                    .any(|o| o.name == name);
+                if !known {
+                    return Err(BackendError::invalid_input(format!(
+                        "Unknown keyboard option '{}'",
+                        name
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check keyboard settings before they are written. Settings using a custom keymap
+/// file or other rules than evdev, and systems without the XKB rules, are not
+/// checked.
+pub fn validate_keyboard_settings(
+    layout: Option<&str>,
+    variant: Option<&str>,
+    options: Option<&str>,
+    rules: Option<&str>,
+    file: Option<&str>,
+) -> Result<(), BackendError> {
+    let is_set = |value: Option<&str>| value.is_some_and(|v| !v.trim().is_empty());
+    if is_set(file) || (is_set(rules) && rules.map(str::trim) != Some("evdev")) {
+        return Ok(());
+    }
+
+    match load_xkb_catalog() {
+        Ok(catalog) => catalog.check(layout, variant, options),
+        Err(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE xkbConfigRegistry SYSTEM "xkb.dtd">
+<xkbConfigRegistry version="1.1">
+  <modelList>
+    <model>
+      <configItem>
+        <name>pc105</name>
+        <description>Generic 105-key PC</description>
+      </configItem>
+    </model>
+  </modelList>
+  <layoutList>
+    <layout>
+      <configItem>
+        <name>us</name>
+        <shortDescription>en</shortDescription>
+        <description>English (US)</description>
+        <languageList><iso639Id>eng</iso639Id></languageList>
+      </configItem>
+      <variantList>
+        <variant>
+          <configItem>
+            <name>dvorak</name>
+            <description>English (Dvorak)</description>
+          </configItem>
+        </variant>
+        <!-- <variant><configItem><name>commented</name></configItem></variant> -->
+      </variantList>
+    </layout>
+    <layout>
+      <configItem>
+        <name>de</name>
+        <description>German</description>
+      </configItem>
+      <variantList/>
+    </layout>
+  </layoutList>
+  <optionList>
+    <group allowMultipleSelection="true">
+      <configItem>
+        <name>grp</name>
+        <description>Switching to another layout</description>
+      </configItem>
+      <option>
+        <configItem>
+          <name>grp:alt_shift_toggle</name>
+          <description>Alt+Shift &amp; more</description>
+        </configItem>
+      </option>
+    </group>
+    <group>
+      <configItem>
+        <name>caps</name>
+        <description>Caps Lock behavior</description>
+      </configItem>
+      <option>
+        <configItem>
+          <name>caps:escape</name>
+          <description>Make Caps Lock an additional Esc</description>
+        </configItem>
+      </option>
+    </group>
+  </optionList>
+</xkbConfigRegistry>
+"#;
+
+    const LST: &str = "! model
+  pc105           Generic 105-key PC
+
+! layout
+  us              English (US)
+  de              German
+
+! variant
+  dvorak          us: English (Dvorak)
+  nodeadkeys      de: German (no dead keys)
+  orphan          xx: Not a known layout
+
+! option
+  grp                  Switching to another layout
+  grp:alt_shift_toggle Alt+Shift
+  caps:escape          Make Caps Lock an additional Esc
+";
+
+    fn assert_catalog(catalog: &XkbCatalog) {
+        assert_eq!(catalog.models.len(), 1);
+        assert_eq!(catalog.models[0].name, "pc105");
+        assert_eq!(catalog.models[0].description, "Generic 105-key PC");
+
+        let us = &catalog.layouts[0];
+        assert_eq!(
+            (us.name.as_str(), us.description.as_str()),
+            ("us", "English (US)")
+        );
+        assert_eq!(us.variants.len(), 1);
+        assert_eq!(us.variants[0].name, "dvorak");
+        assert_eq!(us.variants[0].description, "English (Dvorak)");
+        assert_eq!(catalog.layouts[1].name, "de");
+    }
+
+    #[test]
+    fn parses_the_xml_registry() {
+        let catalog = parse_xml(XML);
+        assert_catalog(&catalog);
+
+        assert!(catalog.layouts[1].variants.is_empty());
+        assert_eq!(catalog.option_groups.len(), 2);
+        let grp = &catalog.option_groups[0];
+        assert_eq!(grp.name, "grp");
+        assert!(grp.multiple);
+        assert_eq!(grp.options[0].name, "grp:alt_shift_toggle");
+        assert_eq!(grp.options[0].description, "Alt+Shift & more");
+        assert!(!catalog.option_groups[1].multiple);
+    }
+
+    #[test]
+    fn parses_the_lst_file() {
+        let catalog = parse_lst(LST);
+        assert_catalog(&catalog);
+
+        assert_eq!(catalog.layouts[1].variants[0].name, "nodeadkeys");
+        let grp = &catalog.option_groups[0];
+        assert_eq!(grp.name, "grp");
+        assert_eq!(grp.options.len(), 1);
+        // `caps` has no group line, so its option has nowhere to go
+        assert_eq!(catalog.option_groups.len(), 1);
+    }
+
+    #[test]
+    fn survives_malformed_input() {
+        for xml in [
+            "",
+            "<",
+            "<layoutList><layout><configItem><name>us",
+            "</a></b><c/>",
+        ] {
+            let catalog = parse_xml(xml);
+            assert!(
+                catalog
+                    .layouts
+                    .iter()
+                    .all(|l| l.name.is_empty() || l.name == "us")
+            );
+        }
+        assert!(parse_lst("garbage\n!\n  \n").layouts.is_empty());
+    }
+
+    #[test]
+    fn checks_keyboard_settings() {
+        let catalog = parse_xml(XML);
+
+        assert!(
+            catalog
+                .check(Some("us,de"), Some("dvorak,"), Some("grp:alt_shift_toggle"))
+                .is_ok()
+        );
+        assert!(
+            catalog
+                .check(Some("us, de"), Some(",  "), Some("caps:escape, "))
+                .is_ok()
+        );
+        assert!(catalog.check(None, None, None).is_ok());
+        // Variables and empty values aren't checked
+        assert!(
+            catalog
+                .check(Some("$layout"), Some("$variant"), Some(""))
+                .is_ok()
+        );
+
+        assert!(catalog.check(Some("us,xx"), None, None).is_err());
+        assert!(catalog.check(Some("us"), Some("colemak"), None).is_err());
+        assert!(
+            catalog
+                .check(Some("us"), Some("dvorak,dvorak"), None)
+                .is_err()
+        );
+        assert!(catalog.check(Some("de"), Some("dvorak"), None).is_err());
+        assert!(catalog.check(None, None, Some("caps:nonexistent")).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+import { convertFileSrc } from "@tauri-apps/api/core";
 import { Music, Volume2, VolumeOff } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent } from "@/components/ui/card";
@@ -22,7 +23,15 @@ export function AudioStreamCard({
 			<CardContent className="pt-4">
 				<div className="flex items-start gap-3">
 					<div className="flex h-10 w-10 items-center justify-center rounded-lg bg-primary/10 shrink-0">
-						<Music className="h-5 w-5 text-primary" />
+						{stream.icon ? (
+							<img
+								src={convertFileSrc(stream.icon)}
+								alt=""
+								className="h-6 w-6 object-contain"
+							/>
+						) : (
+							<Music className="h-5 w-5 text-primary" />
+						)}
 					</div>
 					<div className="flex-1 min-w-0">
 						<div className="font-semibold text-foreground truncate mb-1">
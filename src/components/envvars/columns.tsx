@@ -14,10 +14,10 @@ interface EditingState {
 interface CreateEnvVarColumnsOptions {
 	editing: EditingState;
 	onStartEdit: (rowIndex: number, value: string) => void;
-	onSaveEdit: (envIndex: number, name: string) => void;
+	onSaveEdit: (id: string, name: string) => void;
 	onCancelEdit: () => void;
 	onEditValueChange: (value: string) => void;
-	onDelete: (envIndex: number) => void;
+	onDelete: (id: string) => void;
 }
 
 export function createEnvVarColumns({
@@ -57,7 +57,7 @@ export function createEnvVarColumns({
 								disabled={editing.editLoading}
 								onKeyDown={(e) => {
 									if (e.key === "Enter") {
-										onSaveEdit(row.original.index, row.original.name);
+										onSaveEdit(row.original.id, row.original.name);
 									} else if (e.key === "Escape") {
 										onCancelEdit();
 									}
@@ -67,7 +67,7 @@ export function createEnvVarColumns({
 							<Button
 								size="icon"
 								variant="ghost"
-								onClick={() => onSaveEdit(row.original.index, row.original.name)}
+								onClick={() => onSaveEdit(row.original.id, row.original.name)}
 								disabled={editing.editLoading}
 							>
 								<Check className="h-4 w-4" />
@@ -109,7 +109,7 @@ export function createEnvVarColumns({
 					<Button
 						size="icon"
 						variant="ghost"
-						onClick={() => onDelete(row.original.index)}
+						onClick={() => onDelete(row.original.id)}
 					>
 						<Trash2 className="h-4 w-4 text-destructive" />
 					</Button>
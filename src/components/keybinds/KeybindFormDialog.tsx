@@ -18,11 +18,12 @@ import {
   MODIFIER_OPTIONS,
 } from "@/constants/keybinds";
 import { getDispatcherDescription } from "@/utils/keybinds";
+import { getErrorMessage } from "@/utils/errors";
 
 interface KeybindFormDialogProps {
   open: boolean;
   onOpenChange: (open: boolean) => void;
-  editingKeybind?: { keybind: Keybind; index: number } | null;
+  editingKeybind?: Keybind | null;
   onSuccess: () => void;
   isBindu?: boolean;
 }
@@ -47,10 +48,10 @@ export function KeybindFormDialog({
   // Reset form when dialog opens/closes or editing changes
   useEffect(() => {
     if (open && editingKeybind) {
-      setFormModifiers(editingKeybind.keybind.modifiers);
-      setFormKey(editingKeybind.keybind.key);
-      setFormDispatcher(editingKeybind.keybind.dispatcher);
-      setFormParams(editingKeybind.keybind.params);
+      setFormModifiers(editingKeybind.modifiers);
+      setFormKey(editingKeybind.key);
+      setFormDispatcher(editingKeybind.dispatcher);
+      setFormParams(editingKeybind.params);
       setFormError(null);
     } else if (open && !editingKeybind) {
       resetForm();
@@ -109,7 +110,7 @@ export function KeybindFormDialog({
 
     const args = !isBindu && editingKeybind
       ? {
-          index: editingKeybind.index,
+          id: editingKeybind.id,
           modifiers: formModifiers,
           key: formKey.trim(),
           dispatcher: formDispatcher.trim(),
@@ -129,7 +130,7 @@ export function KeybindFormDialog({
         onSuccess();
       })
       .catch((err) => {
-        setFormError(err as string);
+        setFormError(getErrorMessage(err));
       })
       .finally(() => {
         setFormLoading(false);
@@ -15,8 +15,8 @@ import { ModifierBadges } from "./ModifierBadges";
 
 interface CreateKeybindColumnsOptions {
 	variables: Variable[];
-	onEdit?: (keybind: Keybind, index: number) => void;
-	onDelete: (index: number) => void;
+	onEdit?: (keybind: Keybind) => void;
+	onDelete: (id: string) => void;
 }
 
 export function createKeybindColumns({
@@ -71,7 +71,7 @@ export function createKeybindColumns({
 						<Button
 							size="icon"
 							variant="ghost"
-							onClick={() => onEdit(row.original, row.index)}
+							onClick={() => onEdit(row.original)}
 						>
 							<Pencil className="h-4 w-4" />
 						</Button>
@@ -79,7 +79,7 @@ export function createKeybindColumns({
 					<Button
 						size="icon"
 						variant="ghost"
-						onClick={() => onDelete(row.index)}
+						onClick={() => onDelete(row.original.id)}
 					>
 						<Trash2 className="h-4 w-4 text-destructive" />
 					</Button>
@@ -12,6 +12,7 @@ import {
 	CardHeader,
 	CardTitle,
 } from "@/components/ui/card";
+import { getErrorMessage } from "@/utils/errors";
 
 interface SystemInfo {
 	os: string;
@@ -47,7 +48,7 @@ export default function About() {
 				setCachedSystemInfo(info); // Cache for next time
 			})
 			.catch((err) => {
-				setError(err as string);
+				setError(getErrorMessage(err));
 				// Keep showing cached data if available
 				if (!cachedSystemInfo) {
 					setSystemInfo(null);
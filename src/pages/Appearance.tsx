@@ -23,6 +23,7 @@ import {
 	SelectTrigger,
 	SelectValue,
 } from "@/components/ui/select";
+import { getErrorMessage } from "@/utils/errors";
 
 interface Wallpaper {
 	monitor: string;
@@ -55,7 +56,7 @@ export default function Appearance() {
 			);
 			setConfig(hyprpaperConfig);
 		} catch (err) {
-			setError(err as string);
+			setError(getErrorMessage(err));
 			setConfig(null);
 		} finally {
 			setLoading(false);
@@ -144,7 +145,7 @@ export default function Appearance() {
 						await reloadTheme();
 						toast.success("Wallpaper updated and Matugen synced");
 					} catch (matugenErr) {
-						toast.warning(`Wallpaper updated but Matugen failed: ${matugenErr}`);
+						toast.warning(`Wallpaper updated but Matugen failed: ${getErrorMessage(matugenErr)}`);
 					}
 				} else {
 					toast.success("Wallpaper updated successfully");
@@ -153,7 +154,7 @@ export default function Appearance() {
 				await loadConfig();
 			}
 		} catch (err) {
-			toast.error(`Failed to update wallpaper: ${err}`);
+			toast.error(`Failed to update wallpaper: ${getErrorMessage(err)}`);
 		} finally {
 			setUpdating(false);
 		}
@@ -168,7 +169,7 @@ export default function Appearance() {
 				generatorType: generatorType,
 			});
 		} catch (err) {
-			toast.error(`Failed to save preference: ${err}`);
+			toast.error(`Failed to save preference: ${getErrorMessage(err)}`);
 		}
 	};
 
@@ -181,7 +182,7 @@ export default function Appearance() {
 				generatorType: generatorType,
 			});
 		} catch (err) {
-			toast.error(`Failed to save preference: ${err}`);
+			toast.error(`Failed to save preference: ${getErrorMessage(err)}`);
 		}
 	};
 
@@ -194,7 +195,7 @@ export default function Appearance() {
 				generatorType: value,
 			});
 		} catch (err) {
-			toast.error(`Failed to save preference: ${err}`);
+			toast.error(`Failed to save preference: ${getErrorMessage(err)}`);
 		}
 	};
 
@@ -404,7 +405,7 @@ export default function Appearance() {
 											const templatePath = await ensureMatugenTemplate();
 											toast.success(`Template created at ${templatePath}`);
 										} catch (err) {
-											toast.error(`Failed to create template: ${err}`);
+											toast.error(`Failed to create template: ${getErrorMessage(err)}`);
 										}
 									}}
 								>
@@ -427,7 +428,7 @@ export default function Appearance() {
 											await reloadTheme();
 											toast.success("Theme regenerated and reloaded");
 										} catch (err) {
-											toast.error(`Failed to reload theme: ${err}`);
+											toast.error(`Failed to reload theme: ${getErrorMessage(err)}`);
 										}
 									}}
 									disabled={!currentWallpaper}
@@ -7,6 +7,7 @@ import { AudioStreamCard } from "@/components/audio/AudioStreamCard";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import type { AudioDevice, AudioState, AudioStream } from "@/types/audio";
+import { getErrorMessage } from "@/utils/errors";
 
 export default function Audio() {
 	const [audioState, setAudioState] = useState<AudioState | null>(null);
@@ -31,7 +32,7 @@ export default function Audio() {
 			setAudioState(result);
 			setCachedAudioState(result);
 		} catch (err) {
-			setError(err as string);
+			setError(getErrorMessage(err));
 			// Keep cached state on error
 		} finally {
 			setLoading(false);
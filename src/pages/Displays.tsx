@@ -26,6 +26,7 @@ import {
 	SelectTrigger,
 	SelectValue,
 } from "@/components/ui/select";
+import { getErrorMessage } from "@/utils/errors";
 
 interface DisplayMode {
 	width: number;
@@ -66,6 +67,7 @@ export default function Displays() {
 	const [hasChanges, setHasChanges] = useState(false);
 	const [showConfirmDialog, setShowConfirmDialog] = useState(false);
 	const [countdown, setCountdown] = useState(10);
+	const [previewId, setPreviewId] = useState<number | null>(null);
 	const [originalMonitorData, setOriginalMonitorData] = useState<MonitorInfo[]>([]);
 	const countdownRef = useRef<ReturnType<typeof setInterval> | null>(null);
 
@@ -95,7 +97,7 @@ export default function Displays() {
 				setHasChanges(false);
 			})
 			.catch((err) => {
-				setError(err as string);
+				setError(getErrorMessage(err));
 				// Keep showing cached data if available
 				if (cachedMonitors.length === 0) {
 					setMonitors([]);
@@ -178,22 +180,15 @@ export default function Displays() {
 			countdownRef.current = null;
 		}
 
-		// Revert each monitor to original settings
-		for (const monitor of originalMonitorData) {
+		// Restore the settings from before the preview (a no-op if the backend already did)
+		if (previewId !== null) {
 			try {
-				await invoke("apply_monitor_settings", {
-					name: monitor.name,
-					width: monitor.width,
-					height: monitor.height,
-					refreshRate: monitor.refresh_rate,
-					x: monitor.x,
-					y: monitor.y,
-					scale: monitor.scale,
-				});
+				await invoke("cancel_preview", { id: previewId });
 			} catch (err) {
-				toast.error(`Failed to revert ${monitor.name}: ${err}`);
+				toast.error(`Failed to revert display settings: ${getErrorMessage(err)}`);
 			}
 		}
+		setPreviewId(null);
 
 		// Reset UI state
 		const resetSettings: MonitorSettings = {};
@@ -208,7 +203,7 @@ export default function Displays() {
 		setShowConfirmDialog(false);
 		setCountdown(10);
 		toast.success("Display settings reverted");
-	}, [originalMonitorData]);
+	}, [originalMonitorData, previewId]);
 
 	const handleConfirm = async () => {
 		// Clear timer
@@ -217,31 +212,17 @@ export default function Displays() {
 			countdownRef.current = null;
 		}
 
-		// Save settings to config file
-		for (const monitor of displayMonitors) {
-			const settings = monitorSettings[monitor.id];
-			if (!settings) continue;
-
-			const [width, height] = settings.resolution.split("x").map(Number);
-			const refreshRate = parseFloat(settings.refreshRate);
-
-			try {
-				await invoke("save_monitor_settings", {
-					name: monitor.name,
-					width,
-					height,
-					refreshRate,
-					x: monitor.x,
-					y: monitor.y,
-					scale: monitor.scale,
-				});
-			} catch (err) {
-				toast.error(`Failed to save ${monitor.name}: ${err}`);
-				setShowConfirmDialog(false);
-				setCountdown(10);
-				return;
-			}
+		// Keep the previewed settings and save them to the config file
+		try {
+			await invoke("confirm_preview", { id: previewId, persist: true });
+		} catch (err) {
+			toast.error(`Failed to save display settings: ${getErrorMessage(err)}`);
+			setPreviewId(null);
+			setShowConfirmDialog(false);
+			setCountdown(10);
+			return;
 		}
+		setPreviewId(null);
 
 		// Update original data to match new settings
 		setOriginalMonitorData(displayMonitors.map((m) => {
@@ -263,28 +244,33 @@ export default function Displays() {
 	};
 
 	const handleApply = async () => {
-		// Apply settings via hyprctl
-		for (const monitor of displayMonitors) {
+		// Preview the settings; the backend reverts them unless they are confirmed in time
+		const changes = displayMonitors.flatMap((monitor) => {
 			const settings = monitorSettings[monitor.id];
-			if (!settings) continue;
+			if (!settings) return [];
 
 			const [width, height] = settings.resolution.split("x").map(Number);
-			const refreshRate = parseFloat(settings.refreshRate);
+			return [{
+				kind: "monitor",
+				name: monitor.name,
+				width,
+				height,
+				refresh_rate: parseFloat(settings.refreshRate),
+				x: monitor.x,
+				y: monitor.y,
+				scale: monitor.scale,
+			}];
+		});
 
-			try {
-				await invoke("apply_monitor_settings", {
-					name: monitor.name,
-					width,
-					height,
-					refreshRate,
-					x: monitor.x,
-					y: monitor.y,
-					scale: monitor.scale,
-				});
-			} catch (err) {
-				toast.error(`Failed to apply settings for ${monitor.name}: ${err}`);
-				return;
-			}
+		try {
+			const preview = await invoke<{ id: number }>("start_preview", {
+				changes,
+				timeoutSecs: 10,
+			});
+			setPreviewId(preview.id);
+		} catch (err) {
+			toast.error(`Failed to apply display settings: ${getErrorMessage(err)}`);
+			return;
 		}
 
 		// Show confirmation dialog with countdown
@@ -23,6 +23,7 @@ import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import type { EnvVar } from "@/types/envvars";
 import { createEnvVarColumns } from "@/components/envvars";
+import { getErrorMessage } from "@/utils/errors";
 
 function EnvVarsTableSkeleton() {
 	return (
@@ -87,7 +88,7 @@ export default function EnvVars() {
 				setCachedEnvVars(result);
 			})
 			.catch((err) => {
-				setError(err as string);
+				setError(getErrorMessage(err));
 				if (cachedEnvVars.length === 0) {
 					setEnvVars([]);
 				}
@@ -115,7 +116,7 @@ export default function EnvVars() {
 				loadEnvVars();
 			})
 			.catch((err) => {
-				setAddError(err as string);
+				setAddError(getErrorMessage(err));
 			})
 			.finally(() => {
 				setAddLoading(false);
@@ -132,30 +133,30 @@ export default function EnvVars() {
 		setEditValue("");
 	};
 
-	const handleSaveEdit = async (envIndex: number, name: string) => {
+	const handleSaveEdit = async (id: string, name: string) => {
 		setEditLoading(true);
 
-		invoke("edit_env_var", { index: envIndex, name, value: editValue })
+		invoke("edit_env_var", { id, name, value: editValue })
 			.then(() => {
 				setEditingRowIndex(null);
 				setEditValue("");
 				loadEnvVars();
 			})
 			.catch((err) => {
-				setError(err as string);
+				setError(getErrorMessage(err));
 			})
 			.finally(() => {
 				setEditLoading(false);
 			});
 	};
 
-	const handleDeleteEnvVar = async (envIndex: number) => {
-		invoke("delete_env_var", { index: envIndex })
+	const handleDeleteEnvVar = async (id: string) => {
+		invoke("delete_env_var", { id })
 			.then(() => {
 				loadEnvVars();
 			})
 			.catch((err) => {
-				setError(err as string);
+				setError(getErrorMessage(err));
 			});
 	};
 
@@ -19,6 +19,7 @@ import {
   KeybindFormDialog,
   createKeybindColumns,
 } from "@/components/keybinds";
+import { getErrorMessage } from "@/utils/errors";
 
 export default function Keybinds() {
   const [keybinds, setKeybinds] = useState<Keybind[]>([]);
@@ -35,10 +36,7 @@ export default function Keybinds() {
   // Dialog state
   const [showDialog, setShowDialog] = useState(false);
   const [showBinduDialog, setShowBinduDialog] = useState(false);
-  const [editingKeybind, setEditingKeybind] = useState<{
-    keybind: Keybind;
-    index: number;
-  } | null>(null);
+  const [editingKeybind, setEditingKeybind] = useState<Keybind | null>(null);
 
   useEffect(() => {
     loadKeybinds();
@@ -67,7 +65,7 @@ export default function Keybinds() {
         setCachedKeybinds(result); // Cache for next time
       })
       .catch((err) => {
-        setError(err as string);
+        setError(getErrorMessage(err));
         // Keep showing cached data if available
         if (cachedKeybinds.length === 0) {
           setKeybinds([]);
@@ -84,18 +82,18 @@ export default function Keybinds() {
     setShowDialog(true);
   };
 
-  const handleOpenEditDialog = (keybind: Keybind, index: number) => {
-    setEditingKeybind({ keybind, index });
+  const handleOpenEditDialog = (keybind: Keybind) => {
+    setEditingKeybind(keybind);
     setShowDialog(true);
   };
 
-  const handleDeleteKeybind = async (index: number) => {
-    invoke("delete_keybind", { index })
+  const handleDeleteKeybind = async (id: string) => {
+    invoke("delete_keybind", { id })
       .then(() => {
         loadKeybinds();
       })
       .catch((err) => {
-        setError(err as string);
+        setError(getErrorMessage(err));
       });
   };
 
@@ -125,13 +123,13 @@ export default function Keybinds() {
     setShowBinduDialog(true);
   };
 
-  const handleDeleteBindu = async (index: number) => {
-    invoke("delete_bindu", { index })
+  const handleDeleteBindu = async (id: string) => {
+    invoke("delete_bindu", { id })
       .then(() => {
         loadBindus();
       })
       .catch((err) => {
-        setError(err as string);
+        setError(getErrorMessage(err));
       });
   };
 
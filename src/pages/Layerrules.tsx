@@ -12,6 +12,7 @@ import {
 import { Skeleton } from "@/components/ui/skeleton";
 import { Badge } from "@/components/ui/badge";
 import type { Layerrule } from "@/types/layerrules";
+import { getErrorMessage } from "@/utils/errors";
 
 function LayerrulesTableSkeleton() {
   return (
@@ -64,7 +65,7 @@ function LayerruleCard({ name, onDelete }: LayerruleCardProps) {
         setRule(result);
       })
       .catch((err) => {
-        setError(err as string);
+        setError(getErrorMessage(err));
       })
       .finally(() => {
         setLoading(false);
@@ -181,7 +182,7 @@ export default function Layerrules() {
         setCachedRuleNames(result);
       })
       .catch((err) => {
-        setError(err as string);
+        setError(getErrorMessage(err));
         if (cachedRuleNames.length === 0) {
           setRuleNames([]);
         }
@@ -198,7 +199,7 @@ export default function Layerrules() {
         loadLayerrules();
       })
       .catch((err) => {
-        setError(err as string);
+        setError(getErrorMessage(err));
       });
   };
 
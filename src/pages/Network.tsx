@@ -10,6 +10,7 @@ import {
 import { WifiNetworkCard } from "@/components/network/WifiNetworkCard";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { getErrorMessage } from "@/utils/errors";
 
 interface NetworkInterface {
 	name: string;
@@ -80,7 +81,7 @@ export default function Network() {
 				setInterfaces(result);
 			})
 			.catch((err) => {
-				setError(err as string);
+				setError(getErrorMessage(err));
 			})
 			.finally(() => {
 				setLoadingInterfaces(false);
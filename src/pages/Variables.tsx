@@ -23,6 +23,7 @@ import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import type { Variable } from "@/types/variables";
 import { createVariableColumns } from "@/components/variables";
+import { getErrorMessage } from "@/utils/errors";
 
 function VariablesTableSkeleton() {
 	return (
@@ -87,7 +88,7 @@ export default function Variables() {
 				setCachedVariables(result); // Cache for next time
 			})
 			.catch((err) => {
-				setError(err as string);
+				setError(getErrorMessage(err));
 				// Keep showing cached data if available
 				if (cachedVariables.length === 0) {
 					setVariables([]);
@@ -117,7 +118,7 @@ export default function Variables() {
 				loadVariables();
 			})
 			.catch((err) => {
-				setAddError(err as string);
+				setAddError(getErrorMessage(err));
 			})
 			.finally(() => {
 				setAddLoading(false);
@@ -145,7 +146,7 @@ export default function Variables() {
 				loadVariables();
 			})
 			.catch((err) => {
-				setError(err as string);
+				setError(getErrorMessage(err));
 			})
 			.finally(() => {
 				setEditLoading(false);
@@ -159,7 +160,7 @@ export default function Variables() {
 				loadVariables();
 			})
 			.catch((err) => {
-				setError(err as string);
+				setError(getErrorMessage(err));
 			});
 	};
 
@@ -12,6 +12,7 @@ import {
 import { Skeleton } from "@/components/ui/skeleton";
 import { Badge } from "@/components/ui/badge";
 import type { Windowrule } from "@/types/windowrules";
+import { getErrorMessage } from "@/utils/errors";
 
 function WindowrulesTableSkeleton() {
   return (
@@ -64,7 +65,7 @@ function WindowruleCard({ name, onDelete }: WindowruleCardProps) {
         setRule(result);
       })
       .catch((err) => {
-        setError(err as string);
+        setError(getErrorMessage(err));
       })
       .finally(() => {
         setLoading(false);
@@ -181,7 +182,7 @@ export default function Windowrules() {
         setCachedRuleNames(result);
       })
       .catch((err) => {
-        setError(err as string);
+        setError(getErrorMessage(err));
         if (cachedRuleNames.length === 0) {
           setRuleNames([]);
         }
@@ -198,7 +199,7 @@ export default function Windowrules() {
         loadWindowrules();
       })
       .catch((err) => {
-        setError(err as string);
+        setError(getErrorMessage(err));
       });
   };
 
@@ -14,6 +14,7 @@ export interface AudioStream {
   media_name?: string;
   volume: number;
   muted: boolean;
+  icon?: string | null;
 }
 
 export interface AudioState {
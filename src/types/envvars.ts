@@ -1,6 +1,7 @@
 export interface EnvVar {
+	id: string;
 	name: string;
 	value: string;
-	index: number;
 	source_file?: string;
+	line?: number;
 }
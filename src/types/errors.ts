@@ -0,0 +1,20 @@
+export type ErrorCode =
+  | "config_not_found"
+  | "parse_failed"
+  | "conflict"
+  | "invalid_input"
+  | "not_found"
+  | "io"
+  | "tool_missing"
+  | "command_failed"
+  | "hyprland_not_running"
+  | "other";
+
+export interface BackendError {
+  code: ErrorCode;
+  message: string;
+  file: string | null;
+  line: number | null;
+  cause: string | null;
+  hint: string | null;
+}
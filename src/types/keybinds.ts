@@ -1,6 +1,9 @@
 export interface Keybind {
+  id: string;
   modifiers: string[];
   key: string;
   dispatcher: string;
   params: string;
+  source_file?: string;
+  line?: number;
 }
@@ -2,4 +2,5 @@ export interface Variable {
   name: string;
   value: string;
   source_file?: string;
+  is_color: boolean;
 }
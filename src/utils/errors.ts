@@ -0,0 +1,24 @@
+import type { BackendError } from "@/types/errors";
+
+// Every Tauri command rejects with a BackendError
+export const isBackendError = (err: unknown): err is BackendError =>
+  typeof err === "object" && err !== null && "code" in err && "message" in err;
+
+// Human readable text for a rejected invoke, including cause and hint
+export const getErrorMessage = (err: unknown): string => {
+  if (!isBackendError(err)) {
+    return String(err);
+  }
+
+  let text = err.message;
+  if (err.file && err.line !== null) {
+    text += ` (${err.file}:${err.line})`;
+  }
+  if (err.cause) {
+    text += `: ${err.cause}`;
+  }
+  if (err.hint) {
+    text += `. ${err.hint}`;
+  }
+  return text;
+};